@@ -0,0 +1,227 @@
+use near_sdk::env;
+use near_sdk::serde::Serialize;
+
+/// Errors of the contract. Panic message is `{code}: {description}`, codes are stable and can be matched by clients.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContractError {
+    NoAccess,
+    PrivateFunction,
+    NotEnoughStorageDeposit,
+    PositiveDepositRequired,
+    OneYoctoRequired,
+    RequestNotFound,
+    RequestNotWhitelisted,
+    RequestAlreadyExists,
+    RequestKeyAlreadyUsed,
+    RequestOfDifferentAccount,
+    RequestExpired,
+    RequestAlreadyStarted,
+    RequestContactMissing,
+    RequestKeyOrAccountRequired,
+    RequestWithoutOtp,
+    ContactValueEmpty,
+    ContactValueInvalid,
+    ContactAccountIdMissing,
+    ContactAlreadyRegistered,
+    ContactConfusable,
+    ContactStale,
+    ContactCategoryMismatch,
+    ContactNotWhitelisted,
+    ContactNotFound,
+    NotContactOwner,
+    TooManyFailedAttempts,
+    SignatureExpired,
+    NonceAlreadyUsed,
+    InvalidSignature,
+    UnsupportedKeyType,
+    VerifierKeyNotFound,
+    VerifierNotFound,
+    CategoriesEmpty,
+    CategoryNotFound,
+    CategoryAlreadyExists,
+    CategoryDeprecated,
+    CategoryIdInvalid,
+    CategoryWithoutStableId,
+    EmailDomainRuleNotFound,
+    LimitTooHigh,
+    Deprecated,
+    OldStateNotFound,
+    MigrationInProgress,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ErrorDescription {
+    pub code: &'static str,
+    pub description: &'static str,
+}
+
+impl ContractError {
+    pub fn all() -> Vec<ContractError> {
+        vec![
+            ContractError::NoAccess,
+            ContractError::PrivateFunction,
+            ContractError::NotEnoughStorageDeposit,
+            ContractError::PositiveDepositRequired,
+            ContractError::OneYoctoRequired,
+            ContractError::RequestNotFound,
+            ContractError::RequestNotWhitelisted,
+            ContractError::RequestAlreadyExists,
+            ContractError::RequestKeyAlreadyUsed,
+            ContractError::RequestOfDifferentAccount,
+            ContractError::RequestExpired,
+            ContractError::RequestAlreadyStarted,
+            ContractError::RequestContactMissing,
+            ContractError::RequestKeyOrAccountRequired,
+            ContractError::RequestWithoutOtp,
+            ContractError::ContactValueEmpty,
+            ContractError::ContactValueInvalid,
+            ContractError::ContactAccountIdMissing,
+            ContractError::ContactAlreadyRegistered,
+            ContractError::ContactConfusable,
+            ContractError::ContactStale,
+            ContractError::ContactCategoryMismatch,
+            ContractError::ContactNotWhitelisted,
+            ContractError::ContactNotFound,
+            ContractError::NotContactOwner,
+            ContractError::TooManyFailedAttempts,
+            ContractError::SignatureExpired,
+            ContractError::NonceAlreadyUsed,
+            ContractError::InvalidSignature,
+            ContractError::UnsupportedKeyType,
+            ContractError::VerifierKeyNotFound,
+            ContractError::VerifierNotFound,
+            ContractError::CategoriesEmpty,
+            ContractError::CategoryNotFound,
+            ContractError::CategoryAlreadyExists,
+            ContractError::CategoryDeprecated,
+            ContractError::CategoryIdInvalid,
+            ContractError::CategoryWithoutStableId,
+            ContractError::EmailDomainRuleNotFound,
+            ContractError::LimitTooHigh,
+            ContractError::Deprecated,
+            ContractError::OldStateNotFound,
+            ContractError::MigrationInProgress,
+        ]
+    }
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            ContractError::NoAccess => "ERR_NO_ACCESS",
+            ContractError::PrivateFunction => "ERR_PRIVATE_FUNCTION",
+            ContractError::NotEnoughStorageDeposit => "ERR_NOT_ENOUGH_STORAGE_DEPOSIT",
+            ContractError::PositiveDepositRequired => "ERR_POSITIVE_DEPOSIT_REQUIRED",
+            ContractError::OneYoctoRequired => "ERR_ONE_YOCTO_REQUIRED",
+            ContractError::RequestNotFound => "ERR_REQUEST_NOT_FOUND",
+            ContractError::RequestNotWhitelisted => "ERR_REQUEST_NOT_WHITELISTED",
+            ContractError::RequestAlreadyExists => "ERR_REQUEST_ALREADY_EXISTS",
+            ContractError::RequestKeyAlreadyUsed => "ERR_REQUEST_KEY_ALREADY_USED",
+            ContractError::RequestOfDifferentAccount => "ERR_REQUEST_OF_DIFFERENT_ACCOUNT",
+            ContractError::RequestExpired => "ERR_REQUEST_EXPIRED",
+            ContractError::RequestAlreadyStarted => "ERR_REQUEST_ALREADY_STARTED",
+            ContractError::RequestContactMissing => "ERR_REQUEST_CONTACT_MISSING",
+            ContractError::RequestKeyOrAccountRequired => "ERR_REQUEST_KEY_OR_ACCOUNT_REQUIRED",
+            ContractError::RequestWithoutOtp => "ERR_REQUEST_WITHOUT_OTP",
+            ContractError::ContactValueEmpty => "ERR_CONTACT_VALUE_EMPTY",
+            ContractError::ContactValueInvalid => "ERR_CONTACT_VALUE_INVALID",
+            ContractError::ContactAccountIdMissing => "ERR_CONTACT_ACCOUNT_ID_MISSING",
+            ContractError::ContactAlreadyRegistered => "ERR_CONTACT_ALREADY_REGISTERED",
+            ContractError::ContactConfusable => "ERR_CONTACT_CONFUSABLE",
+            ContractError::ContactStale => "ERR_CONTACT_STALE",
+            ContractError::ContactCategoryMismatch => "ERR_CONTACT_CATEGORY_MISMATCH",
+            ContractError::ContactNotWhitelisted => "ERR_CONTACT_NOT_WHITELISTED",
+            ContractError::ContactNotFound => "ERR_CONTACT_NOT_FOUND",
+            ContractError::NotContactOwner => "ERR_NOT_CONTACT_OWNER",
+            ContractError::TooManyFailedAttempts => "ERR_TOO_MANY_FAILED_ATTEMPTS",
+            ContractError::SignatureExpired => "ERR_SIGNATURE_EXPIRED",
+            ContractError::NonceAlreadyUsed => "ERR_NONCE_ALREADY_USED",
+            ContractError::InvalidSignature => "ERR_INVALID_SIGNATURE",
+            ContractError::UnsupportedKeyType => "ERR_UNSUPPORTED_KEY_TYPE",
+            ContractError::VerifierKeyNotFound => "ERR_VERIFIER_KEY_NOT_FOUND",
+            ContractError::VerifierNotFound => "ERR_VERIFIER_NOT_FOUND",
+            ContractError::CategoriesEmpty => "ERR_CATEGORIES_EMPTY",
+            ContractError::CategoryNotFound => "ERR_CATEGORY_NOT_FOUND",
+            ContractError::CategoryAlreadyExists => "ERR_CATEGORY_ALREADY_EXISTS",
+            ContractError::CategoryDeprecated => "ERR_CATEGORY_DEPRECATED",
+            ContractError::CategoryIdInvalid => "ERR_CATEGORY_ID_INVALID",
+            ContractError::CategoryWithoutStableId => "ERR_CATEGORY_WITHOUT_STABLE_ID",
+            ContractError::EmailDomainRuleNotFound => "ERR_EMAIL_DOMAIN_RULE_NOT_FOUND",
+            ContractError::LimitTooHigh => "ERR_LIMIT_TOO_HIGH",
+            ContractError::Deprecated => "ERR_DEPRECATED",
+            ContractError::OldStateNotFound => "ERR_OLD_STATE_NOT_FOUND",
+            ContractError::MigrationInProgress => "ERR_MIGRATION_IN_PROGRESS",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ContractError::NoAccess => "No access",
+            ContractError::PrivateFunction => "Private function",
+            ContractError::NotEnoughStorageDeposit => "Not enough storage deposit, see `storage_amount`",
+            ContractError::PositiveDepositRequired => "Requires positive deposit",
+            ContractError::OneYoctoRequired => "Requires attached deposit of exactly 1 yoctoNEAR",
+            ContractError::RequestNotFound => "Request not found",
+            ContractError::RequestNotWhitelisted => "Only whitelisted keys allowed",
+            ContractError::RequestAlreadyExists => "Request for this account already exist. Please remove it to continue",
+            ContractError::RequestKeyAlreadyUsed => "Request key already used by another account",
+            ContractError::RequestOfDifferentAccount => "Request whitelisted for different account",
+            ContractError::RequestExpired => "Request expired",
+            ContractError::RequestAlreadyStarted => "Contact already exists for this request",
+            ContractError::RequestContactMissing => "Confirm of undefined contact",
+            ContractError::RequestKeyOrAccountRequired => "Request key or account is required",
+            ContractError::RequestWithoutOtp => "Request isn't confirmed with a code",
+            ContractError::ContactValueEmpty => "Contact value is empty",
+            ContractError::ContactValueInvalid => "Contact value is invalid for the category",
+            ContractError::ContactAccountIdMissing => "Contact account_id is missing",
+            ContractError::ContactAlreadyRegistered => "Contact already registered",
+            ContractError::ContactConfusable => "Contact is confusable with a registered contact",
+            ContractError::ContactStale => "Contact verification is outdated, contact must be verified again",
+            ContractError::ContactCategoryMismatch => "Contact category doesn't match request",
+            ContractError::ContactNotWhitelisted => "Contact doesn't match whitelisted contact",
+            ContractError::ContactNotFound => "Contact not found",
+            ContractError::NotContactOwner => "Not an owner of this contact",
+            ContractError::TooManyFailedAttempts => "Too many failed attempts. Please try again later",
+            ContractError::SignatureExpired => "Signature expired",
+            ContractError::NonceAlreadyUsed => "Nonce already used",
+            ContractError::InvalidSignature => "Invalid signature",
+            ContractError::UnsupportedKeyType => "Only ED25519 keys are supported",
+            ContractError::VerifierKeyNotFound => "Verifier key not found",
+            ContractError::VerifierNotFound => "Verifier not found",
+            ContractError::CategoriesEmpty => "Categories are empty",
+            ContractError::CategoryNotFound => "Category not found",
+            ContractError::CategoryAlreadyExists => "Category already exists",
+            ContractError::CategoryDeprecated => "Category is deprecated",
+            ContractError::CategoryIdInvalid => "Category id can't be empty or contain `:`",
+            ContractError::CategoryWithoutStableId => "Contacts of the category are not identified by stable id",
+            ContractError::EmailDomainRuleNotFound => "Email domain rule not found",
+            ContractError::LimitTooHigh => "Abort. Limit > 100",
+            ContractError::Deprecated => "Deprecated. Use `get_account_for_contact` instead",
+            ContractError::OldStateNotFound => "Old state doesn't exist",
+            ContractError::MigrationInProgress => "Contacts of version 1 are being migrated, try again later",
+        }
+    }
+
+    pub fn panic(&self) -> ! {
+        env::panic(format!("{}: {}", self.code(), self.description()).as_bytes())
+    }
+
+    /// Panics with `{code}: {description}: {details}`, details are machine readable as well.
+    pub fn panic_with(&self, details: &str) -> ! {
+        env::panic(format!("{}: {}: {}", self.code(), self.description(), details).as_bytes())
+    }
+}
+
+impl From<ContractError> for ErrorDescription {
+    fn from(error: ContractError) -> Self {
+        ErrorDescription {
+            code: error.code(),
+            description: error.description(),
+        }
+    }
+}
+
+pub(crate) fn require(condition: bool, error: ContractError) {
+    if !condition {
+        error.panic()
+    }
+}
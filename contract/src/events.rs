@@ -0,0 +1,188 @@
+use near_sdk::serde::Serialize;
+use near_sdk::serde_json;
+use near_sdk::json_types::{Base58PublicKey, U128, U64};
+use near_sdk::{env, AccountId};
+
+use crate::{Category, CategoryId, Contact, DiscoverySettings, EmailDomainRule, RequestKey};
+
+/// Events are logged following NEP-297 as `EVENT_JSON:{"standard":"nearauth","version":"1.0.0","event":...,"data":[...]}`.
+pub const EVENT_STANDARD: &str = "nearauth";
+pub const EVENT_STANDARD_VERSION: &str = "1.0.0";
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+struct EventLog<'a> {
+    standard: &'static str,
+    version: &'static str,
+    #[serde(flatten)]
+    event: &'a Event,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(tag = "event", content = "data", rename_all = "snake_case")]
+pub enum Event {
+    RequestCreated(Vec<RequestData>),
+    RequestStarted(Vec<RequestData>),
+    RequestConfirmed(Vec<RequestData>),
+    RequestRemoved(Vec<RequestRemovedData>),
+    ConfirmFailed(Vec<ConfirmFailedData>),
+    ContactBound(Vec<ContactData>),
+    ContactUnbound(Vec<ContactData>),
+    ContactStableIdSet(Vec<ContactStableIdData>),
+    ContactDisplayUpdated(Vec<ContactDisplayData>),
+    ContactReverified(Vec<ContactReverifiedData>),
+    ContactPrimarySet(Vec<ContactPrimaryData>),
+    DiscoverySettingsSet(Vec<DiscoverySettingsData>),
+    StorageDeposited(Vec<StorageData>),
+    StorageWithdrawn(Vec<StorageData>),
+    TokensSent(Vec<TokensSentData>),
+    VerifierAdded(Vec<VerifierData>),
+    VerifierRemoved(Vec<VerifierData>),
+    VerifierKeyAdded(Vec<VerifierKeyData>),
+    VerifierKeyRemoved(Vec<VerifierKeyData>),
+    CategoryAdded(Vec<CategoryData>),
+    CategoryDeprecated(Vec<CategoryData>),
+    CategoryUpdated(Vec<CategoryData>),
+    EmailDomainRuleSet(Vec<EmailDomainRuleData>),
+    EmailDomainRuleRemoved(Vec<EmailDomainRuleData>),
+}
+
+impl Event {
+    pub fn emit(&self) {
+        let event_log = EventLog {
+            standard: EVENT_STANDARD,
+            version: EVENT_STANDARD_VERSION,
+            event: self,
+        };
+        env::log(format!("EVENT_JSON:{}", serde_json::to_string(&event_log).unwrap()).as_bytes());
+    }
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RequestData {
+    pub account_id: AccountId,
+    pub request_key: RequestKey,
+    pub category: CategoryId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum RequestRemovedReason {
+    User,
+    Expired,
+    OtpAttempts,
+    Admin,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct RequestRemovedData {
+    pub account_id: AccountId,
+    pub request_key: RequestKey,
+    pub category: CategoryId,
+    pub reason: RequestRemovedReason,
+    pub refund: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ConfirmFailedData {
+    pub account_id: AccountId,
+    pub remaining_attempts: u32,
+}
+
+/// `storage` is spent for bound contacts and unlocked for unbound ones.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContactData {
+    pub account_id: AccountId,
+    pub contacts: Vec<Contact>,
+    pub storage: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContactStableIdData {
+    pub account_id: AccountId,
+    pub contact: Contact,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContactDisplayData {
+    pub account_id: AccountId,
+    pub contact: Contact,
+    pub previous_value: String,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContactReverifiedData {
+    pub account_id: AccountId,
+    pub contact: Contact,
+    pub verified_at: U64,
+    pub verified_by: AccountId,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct ContactPrimaryData {
+    pub account_id: AccountId,
+    pub contact: Contact,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DiscoverySettingsData {
+    pub account_id: AccountId,
+    pub settings: DiscoverySettings,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct StorageData {
+    pub account_id: AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct TokensSentData {
+    pub sender_id: AccountId,
+    pub receiver_id: AccountId,
+    pub amount: U128,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifierData {
+    pub account_id: AccountId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<CategoryId>>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct VerifierKeyData {
+    pub public_key: Base58PublicKey,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub categories: Option<Vec<CategoryId>>,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct CategoryData {
+    pub category_id: CategoryId,
+    pub category: Category,
+}
+
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EmailDomainRuleData {
+    pub domain: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rule: Option<EmailDomainRule>,
+}
@@ -3,14 +3,24 @@ use near_sdk::serde::{Deserialize, Serialize};
 use std::convert::TryFrom;
 use near_sdk::wee_alloc;
 use near_sdk::{env, near_bindgen, AccountId, Balance, Promise, PanicOnDefault};
-use near_sdk::json_types::{ValidAccountId, U128};
-use near_sdk::collections::{LookupMap, UnorderedMap};
+use near_sdk::json_types::{Base58PublicKey, Base64VecU8, CurveType, ValidAccountId, U128, U64};
+use near_sdk::collections::{LookupMap, LookupSet, UnorderedMap};
 use std::collections::HashMap;
 use sha256::digest;
+use ed25519_dalek::Verifier;
+use unicode_normalization::UnicodeNormalization;
+
+mod errors;
+mod events;
+mod validators;
+use errors::*;
+use events::*;
+pub use validators::*;
 
 type SecretKey = String;
 type RequestKey = String;
 type ContactStringified = String;
+type CategoryId = String;
 
 /// Price per 1 byte of storage from mainnet config after `0.18` release and protocol version `42`.
 /// It's 10 times lower than the genesis price.
@@ -18,6 +28,31 @@ pub const STORAGE_PRICE_PER_BYTE: Balance = 10_000_000_000_000_000_000;
 const WHITELIST_STORAGE_COST: u128 = 10_000_000_000_000_000_000_000;
 //0.01
 const WHITELIST_FEE: u128 = 1_500_000_000_000_000_000_000; //0.0015
+/// Time in nanoseconds after which a pending request can't be used anymore and may be swept.
+const REQUEST_TTL: u64 = 86_400_000_000_000; // 24 hours
+/// Number of failed `confirm_auth` calls after which the account is locked until the end of the window.
+const MAX_FAILED_CONFIRMATIONS: u32 = 5;
+const FAILED_CONFIRMATIONS_WINDOW: u64 = 3_600_000_000_000; // 1 hour
+/// Short numeric codes can be guessed, so OTP requests live shortly and allow few attempts.
+const OTP_TTL: u64 = 600_000_000_000; // 10 minutes
+const OTP_MAX_ATTEMPTS: u8 = 3;
+
+const MAX_SCANNED_ENTRIES: u64 = 500; // entries read by an enumeration view to fill a page
+
+/// Country calling codes where national numbers are dialed with trunk prefix `0`, which isn't a part of the E.164 number.
+/// Italian numbers keep their leading `0`, NANP and Russian numbers use other prefixes.
+const TRUNK_PREFIX_COUNTRY_CODES: &[u16] = &[
+    20, 27, 31, 32, 33, 40, 41, 43, 44, 46, 49, 51, 54, 61, 62, 63, 64, 66, 81, 82, 84, 86, 90, 91, 92, 93, 94, 98,
+    212, 213, 216, 233, 234, 254, 255, 256, 353, 358, 359, 380, 381, 385, 880, 886, 964, 966, 971, 972,
+];
+/// Ids of the categories registered on `new`, other categories are added with `add_category`.
+pub const CATEGORY_EMAIL: &str = "Email";
+pub const CATEGORY_TELEGRAM: &str = "Telegram";
+pub const CATEGORY_TWITTER: &str = "Twitter";
+pub const CATEGORY_GITHUB: &str = "Github";
+pub const CATEGORY_NEAR_GOV_FORUM: &str = "NearGovForum";
+pub const CATEGORY_PHONE: &str = "Phone";
+pub const CATEGORY_DISCORD: &str = "Discord";
 
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
@@ -26,11 +61,23 @@ static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 #[derive(BorshDeserialize, BorshSerialize, PanicOnDefault)]
 pub struct Contract {
     master_account_id: AccountId,
-    accounts: UnorderedMap<AccountId, Vec<Contact>>, // main object, contacts of account
+    accounts: UnorderedMap<AccountId, Vec<VersionedContactRecord>>, // main object, contacts of account
     accounts_for_contacts: UnorderedMap<ContactStringified, AccountId>, // object to find owner of the contact
     requests: UnorderedMap<RequestKey, Request>, // pending requests
     storage_deposits: LookupMap<AccountId, Balance>,
     version: u16,
+    request_keys: LookupMap<AccountId, Vec<RequestKey>>, // object to find pending requests of the account
+    verifier_keys: UnorderedMap<Base58PublicKey, Vec<CategoryId>>, // keys to sign attestations for `claim_contact` per category
+    used_nonces: LookupSet<u64>, // nonces of claimed attestations
+    verifiers: UnorderedMap<AccountId, Vec<CategoryId>>, // accounts allowed to whitelist requests per category
+    failed_confirmations: LookupMap<AccountId, FailedConfirmations>,
+    categories: UnorderedMap<CategoryId, Category>, // registry of contact categories
+    email_domain_rules: UnorderedMap<String, EmailDomainRule>, // canonicalization of emails per provider domain
+    contact_skeletons: LookupMap<String, ContactStringified>, // UTS #39 skeletons of bound contacts to reject confusables
+    contact_salt: String, // public salt of the hashes of private contacts
+    discovery_settings: LookupMap<AccountId, DiscoverySettings>, // accounts and contacts hidden from enumeration views
+    legacy_accounts: UnorderedMap<AccountId, Vec<ContactV1>>, // contacts of version 1, converted on read and moved by `migrate_legacy_entries`
+    legacy_requests: UnorderedMap<RequestKey, RequestV1>, // requests of version 1, refunded by `migrate_legacy_entries`
 }
 
 /// Helper structure to for keys of the persistent collections.
@@ -42,10 +89,249 @@ pub enum StorageKey {
     StorageDeposits,
     Accounts2, // used after migration_1
     Requests2, // used after migration_1
+    RequestKeys,
+    UsedNonces,
+    Verifiers,
+    FailedConfirmations,
+    Categories,
+    EmailDomainRules,
+    ContactSkeletons,
+    DiscoverySettings,
+    VerifierKeys,
+    Accounts25, // used after migration_25
+    Requests25, // used after migration_25
+}
+
+/// Contact category stored in the registry under its id. Deprecated categories can't be used for new requests,
+/// bound contacts stay available.
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Category {
+    pub name: String,
+    pub requires_stable_id: bool, // contact is identified by numeric `account_id` instead of value
+    pub normalization_rules: Vec<NormalizationRule>, // applied to contact value in order
+    pub validator: Option<ContactValidator>, // checks normalized contact value
+    pub deprecated: bool,
+    pub max_verification_age: Option<u64>, // nanoseconds after `verified_at` when bound contact becomes stale
+    pub private_by_default: bool, // contacts are bound as private unless user chooses otherwise, see `get_private_contact`
+}
+
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum NormalizationRule {
+    Trim,
+    Lowercase,
+    StripPrefix(String),
+    CanonicalEmail, // punycode domain and rules of the provider domain, see `set_email_domain_rule`
+    Nfkc, // Unicode compatibility normalization, e.g. full-width characters
+    E164(Option<u16>), // phone number without formatting, national numbers get the default country calling code if it's set
+}
+
+impl NormalizationRule {
+    pub fn apply(&self, value: String, email_domain_rules: &UnorderedMap<String, EmailDomainRule>) -> String {
+        match self {
+            NormalizationRule::Trim => value.trim().to_string(),
+            NormalizationRule::Lowercase => value.to_lowercase(),
+            NormalizationRule::StripPrefix(prefix) => match value.strip_prefix(prefix.as_str()) {
+                Some(stripped) => stripped.to_string(),
+                None => value
+            },
+            NormalizationRule::CanonicalEmail => NormalizationRule::get_canonical_email(value, email_domain_rules),
+            NormalizationRule::Nfkc => value.nfkc().collect(),
+            NormalizationRule::E164(default_country_code) => NormalizationRule::get_e164_phone(value, *default_country_code),
+        }
+    }
+
+    /// Drops spaces, dashes, dots and brackets, international prefix `00` is replaced with `+`.
+    /// National trunk prefix `0` is dropped when the default country code is one of `TRUNK_PREFIX_COUNTRY_CODES`.
+    fn get_e164_phone(value: String, default_country_code: Option<u16>) -> String {
+        let number: String = value.chars()
+            .filter(|c| !c.is_whitespace() && !"-.()/".contains(*c))
+            .collect();
+
+        if let Some(international_number) = number.strip_prefix("00") {
+            format!("+{}", international_number)
+        } else {
+            match default_country_code {
+                Some(country_code) if !number.starts_with('+') => {
+                    let national_number = match number.strip_prefix('0') {
+                        Some(national_number) if TRUNK_PREFIX_COUNTRY_CODES.contains(&country_code) => national_number,
+                        _ => &number
+                    };
+                    format!("+{}{}", country_code, national_number)
+                }
+                _ => number
+            }
+        }
+    }
+
+    fn get_canonical_email(value: String, email_domain_rules: &UnorderedMap<String, EmailDomainRule>) -> String {
+        let (local_part, domain) = match value.rsplit_once('@') {
+            Some((local_part, domain)) => (local_part, domain),
+            None => return value
+        };
+        // invalid domains are kept as is and rejected by validator
+        let domain = idna::domain_to_ascii(domain).unwrap_or_else(|_| domain.to_string());
+
+        match email_domain_rules.get(&domain) {
+            Some(rule) => {
+                let mut local_part = local_part.to_string();
+                if rule.strip_plus_tag {
+                    local_part = local_part.split('+').next().unwrap_or_default().to_string();
+                }
+                if rule.strip_dots {
+                    local_part = local_part.replace('.', "");
+                }
+                format!("{}@{}", local_part, rule.alias_of.unwrap_or(domain))
+            }
+            None => format!("{}@{}", local_part, domain)
+        }
+    }
+}
+
+/// Canonicalization of the mailbox name used by the email provider, `alias_of` is a main domain of the provider.
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct EmailDomainRule {
+    pub strip_dots: bool,
+    pub strip_plus_tag: bool,
+    pub alias_of: Option<String>,
+}
+
+impl EmailDomainRule {
+    /// Rules registered on `new` and on migration, other domains are managed with `set_email_domain_rule`.
+    pub fn defaults() -> Vec<(String, EmailDomainRule)> {
+        vec![
+            ("gmail.com".to_string(), EmailDomainRule {
+                strip_dots: true,
+                strip_plus_tag: true,
+                alias_of: None,
+            }),
+            ("googlemail.com".to_string(), EmailDomainRule {
+                strip_dots: true,
+                strip_plus_tag: true,
+                alias_of: Some("gmail.com".to_string()),
+            }),
+        ]
+    }
+}
+
+impl Category {
+    /// Categories registered on `new` and on migration from the enum of categories.
+    pub fn defaults() -> Vec<(CategoryId, Category)> {
+        let category = |name: &str, requires_stable_id: bool, normalization_rules: Vec<NormalizationRule>, validator: ContactValidator| Category {
+            name: name.to_string(),
+            requires_stable_id,
+            normalization_rules,
+            validator: Some(validator),
+            deprecated: false,
+            max_verification_age: None,
+            private_by_default: false,
+        };
+        let default_rules = vec![NormalizationRule::Nfkc, NormalizationRule::Trim, NormalizationRule::Lowercase];
+
+        vec![
+            (CATEGORY_EMAIL.to_string(), category("Email", false, vec![
+                NormalizationRule::Nfkc,
+                NormalizationRule::Trim,
+                NormalizationRule::Lowercase,
+                NormalizationRule::CanonicalEmail,
+            ], ContactValidator::Email)),
+            (CATEGORY_TELEGRAM.to_string(), category("Telegram", true, vec![
+                NormalizationRule::Nfkc,
+                NormalizationRule::StripPrefix("@".to_string()),
+                NormalizationRule::Trim,
+                NormalizationRule::Lowercase,
+            ], ContactValidator::Telegram)),
+            (CATEGORY_TWITTER.to_string(), category("Twitter", true, default_rules.clone(), ContactValidator::Twitter)),
+            (CATEGORY_GITHUB.to_string(), category("GitHub", true, default_rules.clone(), ContactValidator::Github)),
+            (CATEGORY_NEAR_GOV_FORUM.to_string(), category("NEAR Gov Forum", false, default_rules, ContactValidator::Forum)),
+            (CATEGORY_DISCORD.to_string(), category("Discord", true, vec![
+                NormalizationRule::Nfkc,
+                NormalizationRule::Trim,
+                NormalizationRule::StripPrefix("@".to_string()),
+                NormalizationRule::Lowercase,
+            ], ContactValidator::Discord)),
+            // private by default to keep numbers out of enumeration views, although a salted hash of a phone number
+            // can be recovered by hashing every number, see `get_private_contact`
+            (CATEGORY_PHONE.to_string(), Category {
+                private_by_default: true,
+                ..category("Phone", false, vec![NormalizationRule::Nfkc, NormalizationRule::E164(None)], ContactValidator::Phone)
+            }),
+        ]
+    }
+}
+
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Contact {
+    pub category: CategoryId,
+    pub value: String,
+    pub account_id: Option<U64>, // stable id as a string, ids of some platforms exceed the safe integer range of JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub display_value: Option<String>, // value entered by user if it differs from the normalized one
+}
+
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+#[serde(rename_all = "snake_case")]
+pub enum VerificationMethod {
+    SecretKey,
+    Otp,
+    Attestation,
+    Migration, // contact bound before verification metadata was saved
+    Reverification, // verification of the bound contact was refreshed by the verifier
 }
 
-#[derive(BorshSerialize, BorshDeserialize, Eq, PartialEq, Debug, Serialize, Deserialize, Clone)]
+/// Bound contact with verification metadata. `verified_by` is a verifier account, or a verifier key for attestations.
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize)]
 #[serde(crate = "near_sdk::serde")]
+pub struct ContactRecord {
+    #[serde(flatten)]
+    pub contact: Contact,
+    pub verified_at: u64,
+    pub verified_by: AccountId,
+    pub verification_method: VerificationMethod,
+    pub is_primary: bool, // preferred contact of the owner in its category
+    pub is_private: bool, // contact value is a salted hash, see `get_private_contact`
+}
+
+impl ContactRecord {
+    /// Record of the contact verified in the current block.
+    pub fn new(contact: Contact, verified_by: AccountId, verification_method: VerificationMethod, is_private: bool) -> Self {
+        Self {
+            contact,
+            verified_at: env::block_timestamp(),
+            verified_by,
+            verification_method,
+            is_primary: false,
+            is_private,
+        }
+    }
+}
+
+/// Stored version of `ContactRecord`, new layouts are added as variants and converted on read.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub enum VersionedContactRecord {
+    V1(ContactRecord),
+}
+
+impl From<VersionedContactRecord> for ContactRecord {
+    fn from(record: VersionedContactRecord) -> Self {
+        match record {
+            VersionedContactRecord::V1(record) => record,
+        }
+    }
+}
+
+impl From<ContactRecord> for VersionedContactRecord {
+    fn from(record: ContactRecord) -> Self {
+        VersionedContactRecord::V1(record)
+    }
+}
+
+/// Categories of version 1, names of the variants are ids of the default categories.
+#[derive(BorshDeserialize, BorshSerialize, Debug)]
 pub enum ContactCategories {
     Email,
     Telegram,
@@ -54,32 +340,132 @@ pub enum ContactCategories {
     NearGovForum,
 }
 
-#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq)]
-#[serde(crate = "near_sdk::serde")]
-pub struct Contact {
+/// Layout of `Contact` saved in version 1, before the category registry.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct ContactV1 {
     pub category: ContactCategories,
     pub value: String,
     pub account_id: Option<u64>,
 }
 
-#[derive(BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Clone)]
+impl ContactV1 {
+    /// Key of the contact in `accounts_for_contacts` of version 1, only Telegram contacts were keyed by the stable id.
+    pub fn get_stringified(&self) -> ContactStringified {
+        match (&self.category, self.account_id) {
+            (ContactCategories::Telegram, Some(stable_id)) => format!("{:?}:{}", self.category, stable_id),
+            _ => format!("{:?}:{}", self.category, self.value)
+        }
+    }
+}
+
+impl From<ContactV1> for Contact {
+    fn from(contact: ContactV1) -> Self {
+        Contact {
+            category: format!("{:?}", contact.category),
+            value: contact.value,
+            account_id: contact.account_id.map(U64),
+            display_value: None,
+        }
+    }
+}
+
+/// Layout of `Request` saved in version 1, its key isn't namespaced by account.
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct RequestV1 {
+    pub contact: Option<ContactV1>,
+    pub account_id: AccountId,
+}
+
+/// Contacts of the account excluded from `get_all_*` enumeration views. Lookup by contact is not affected.
+#[derive(Clone, Default, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq)]
 #[serde(crate = "near_sdk::serde")]
-pub struct Contact_v1 {
-    pub category: ContactCategories,
+pub struct DiscoverySettings {
+    pub is_hidden: bool, // all contacts of the account are hidden
+    pub hidden_categories: Vec<CategoryId>,
+    pub hidden_contacts: Vec<Contact>, // bound contacts as they are saved
+}
+
+/// Page of an enumeration view with up to `limit` discoverable entries, hidden and private ones are skipped.
+/// The scan is bounded, so a page may be shorter even if entries are left. `next_index` is the index
+/// to continue from, `None` after the last entry.
+#[derive(Serialize)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Page<T> {
+    pub items: T,
+    pub next_index: Option<U64>,
+}
+
+/// Preferred handle of the account, shown by integrators instead of the account id.
+#[derive(Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub struct DisplayIdentity {
+    pub category: CategoryId,
+    pub handle: String,
+}
+
+#[derive(BorshDeserialize, BorshSerialize)]
+pub struct FailedConfirmations {
+    pub count: u32,
+    pub window_start: u64,
+}
+
+impl FailedConfirmations {
+    pub fn is_window_expired(&self) -> bool {
+        env::block_timestamp() >= self.window_start + FAILED_CONFIRMATIONS_WINDOW
+    }
+}
+
+/// Message signed by verifier for `claim_contact`, serialized with Borsh.
+/// Contact is signed as `get_contact_stringified` of the normalized contact together with its normalized value,
+/// so the handle saved with a stable id is attested as well. Display value isn't a part of it.
+#[derive(BorshSerialize)]
+pub struct ClaimMessage {
+    pub account_id: AccountId,
+    pub contact: ContactStringified,
     pub value: String,
+    pub contract_id: AccountId,
+    pub expires_at: u64,
+    pub nonce: u64,
 }
 
-#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq)]
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, PartialEq)]
 #[serde(crate = "near_sdk::serde")]
 pub struct Request {
     pub contact: Option<Contact>,
     pub account_id: AccountId,
+    pub expires_at: u64,
+    pub category: CategoryId,
+    pub contact_hash: Option<String>, // sha256 of the contact checked by verifier, see `get_contact_hash`
+    pub otp: Option<Otp>, // request confirmed by verifier with a short numeric code, see `confirm_otp`
+    pub whitelisted_by: AccountId,
+    pub is_private: bool, // contact is bound as private, chosen in `start_auth` or by default of the category, `contact` stays readable until then
+}
+
+#[derive(Clone, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq)]
+#[serde(crate = "near_sdk::serde")]
+pub struct Otp {
+    pub attempts: u8, // wrong codes reported by verifier
+}
+
+impl Request {
+    pub fn is_expired(&self) -> bool {
+        env::block_timestamp() >= self.expires_at
+    }
 }
 
 #[near_bindgen]
 impl Contract {
     #[init]
+    /// Master account manages verifiers and is registered as a verifier of all categories until removed.
     pub fn new(master_account_id: ValidAccountId) -> Self {
+        let mut categories = UnorderedMap::new(StorageKey::Categories.try_to_vec().unwrap());
+        for (category_id, category) in Category::defaults() {
+            categories.insert(&category_id, &category);
+        }
+
+        let mut verifiers = UnorderedMap::new(StorageKey::Verifiers.try_to_vec().unwrap());
+        verifiers.insert(master_account_id.as_ref(), &categories.keys().collect());
+
         Self {
             master_account_id: master_account_id.into(),
             accounts: UnorderedMap::new(StorageKey::Accounts.try_to_vec().unwrap()),
@@ -87,349 +473,1295 @@ impl Contract {
             requests: UnorderedMap::new(StorageKey::Requests.try_to_vec().unwrap()),
             storage_deposits: LookupMap::new(StorageKey::StorageDeposits.try_to_vec().unwrap()),
             version: 0,
+            request_keys: LookupMap::new(StorageKey::RequestKeys.try_to_vec().unwrap()),
+            verifier_keys: UnorderedMap::new(StorageKey::VerifierKeys.try_to_vec().unwrap()),
+            used_nonces: LookupSet::new(StorageKey::UsedNonces.try_to_vec().unwrap()),
+            verifiers,
+            failed_confirmations: LookupMap::new(StorageKey::FailedConfirmations.try_to_vec().unwrap()),
+            categories,
+            email_domain_rules: Contract::get_default_email_domain_rules(),
+            contact_skeletons: LookupMap::new(StorageKey::ContactSkeletons.try_to_vec().unwrap()),
+            contact_salt: near_sdk::bs58::encode(env::random_seed()).into_string(),
+            discovery_settings: LookupMap::new(StorageKey::DiscoverySettings.try_to_vec().unwrap()),
+            legacy_accounts: UnorderedMap::new(StorageKey::Accounts2.try_to_vec().unwrap()),
+            legacy_requests: UnorderedMap::new(StorageKey::Requests2.try_to_vec().unwrap()),
         }
     }
 
-    pub fn whitelist_key(&mut self, account_id: ValidAccountId, request_key: RequestKey) {
-        assert!(env::predecessor_account_id() == self.master_account_id, "No access");
+    /// Whitelists request of the account for the contact category. Account may have one pending request per category.
+    /// `request_key` is a sha256 of `{account_id}:{secret_key}`, see `get_request_key_hash`.
+    /// If `contact_hash` is provided, only the contact checked by verifier can be used in `start_auth`.
+    pub fn whitelist_key(&mut self, account_id: ValidAccountId, request_key: RequestKey, category: CategoryId, contact_hash: Option<String>) {
+        require(self.is_verifier(env::predecessor_account_id(), category.clone()), ContractError::NoAccess);
+        self.get_active_category(&category);
 
         let storage_paid = Contract::storage_paid(self, account_id.clone());
 
-        assert!(
-            storage_paid.0 >= WHITELIST_STORAGE_COST,
-            "{} requires minimum storage deposit of {}",
-            account_id, WHITELIST_STORAGE_COST
-        );
+        require(storage_paid.0 >= WHITELIST_STORAGE_COST, ContractError::NotEnoughStorageDeposit);
 
         let account_id_string: AccountId = account_id.into();
 
-        match Contract::get_request_key(self, account_id_string.clone()) {
+        match Contract::get_request_key(self, account_id_string.clone(), category.clone()) {
             None => {
+                require(self.requests.get(&request_key).is_none(), ContractError::RequestKeyAlreadyUsed);
+
                 let request = Request {
                     contact: None,
                     account_id: account_id_string.clone(),
+                    expires_at: env::block_timestamp() + REQUEST_TTL,
+                    category,
+                    contact_hash,
+                    otp: None,
+                    whitelisted_by: env::predecessor_account_id(),
+                    is_private: false,
                 };
 
-                self.requests.insert(&request_key, &request);
+                self.insert_request(&request_key, &request);
+                Event::RequestCreated(vec![RequestData {
+                    account_id: request.account_id,
+                    request_key,
+                    category: request.category,
+                }]).emit();
 
                 // update storage
                 let balance: Balance = storage_paid.0 - WHITELIST_STORAGE_COST;
                 self.storage_deposits.insert(&account_id_string, &balance);
             }
             Some(_) => {
-                env::panic(b"Request for this account already exist. Please remove it to continue")
+                ContractError::RequestAlreadyExists.panic()
             }
         }
     }
 
-    fn prepare_contact(contact: Contact) -> Contact {
-        assert!(!contact.value.is_empty(), "Contact value is empty");
+    /// Whitelists request confirmed with a short numeric code sent to the contact, `start_auth` is not needed.
+    /// Code is kept by verifier off-chain: user gives it back to verifier, which calls `confirm_otp` or `reject_otp`.
+    /// Neither the code nor its hash is saved, `request_key` is a random id chosen by verifier. State is public,
+    /// so a salted hash of a 6-8 digit code is reversed by hashing every code off-chain, and `confirm_auth`
+    /// checking such a hash couldn't limit attempts.
+    /// Request expires after `OTP_TTL` or after `OTP_MAX_ATTEMPTS` wrong codes. Wrong codes are counted
+    /// as verifier reports them, and verifier pays gas of the confirmation instead of user.
+    pub fn whitelist_otp(&mut self, account_id: ValidAccountId, request_key: RequestKey, contact: Contact) {
+        let prepared_contact = self.prepare_contact(contact);
+        require(self.is_verifier(env::predecessor_account_id(), prepared_contact.category.clone()), ContractError::NoAccess);
 
-        if contact.category == ContactCategories::Telegram {
-            assert!(contact.account_id != None, "Telegram account_id is missing");
-        }
+        self.assert_contact_available(&prepared_contact);
 
-        if contact.category == ContactCategories::Telegram && contact.value.chars().nth(0).unwrap() == '@' {
-            Contact {
-                category: ContactCategories::Telegram,
-                value: contact.value[1..contact.value.len()].trim().to_string().to_lowercase(),
-                account_id: contact.account_id,
+        let storage_paid = Contract::storage_paid(self, account_id.clone());
+
+        require(storage_paid.0 >= WHITELIST_STORAGE_COST, ContractError::NotEnoughStorageDeposit);
+
+        let account_id_string: AccountId = account_id.into();
+
+        match Contract::get_request_key(self, account_id_string.clone(), prepared_contact.category.clone()) {
+            None => {
+                require(self.requests.get(&request_key).is_none(), ContractError::RequestKeyAlreadyUsed);
+
+                let is_private_by_default = self.is_private_by_default(&prepared_contact.category);
+                let request = Request {
+                    account_id: account_id_string.clone(),
+                    expires_at: env::block_timestamp() + OTP_TTL,
+                    category: prepared_contact.category.clone(),
+                    contact: Some(prepared_contact),
+                    contact_hash: None,
+                    otp: Some(Otp { attempts: 0 }),
+                    whitelisted_by: env::predecessor_account_id(),
+                    is_private: is_private_by_default,
+                };
+
+                self.insert_request(&request_key, &request);
+                Event::RequestCreated(vec![RequestData {
+                    account_id: request.account_id,
+                    request_key,
+                    category: request.category,
+                }]).emit();
+
+                // update storage
+                let balance: Balance = storage_paid.0 - WHITELIST_STORAGE_COST;
+                self.storage_deposits.insert(&account_id_string, &balance);
             }
-        } else {
-            Contact {
-                category: contact.category,
-                value: contact.value.trim().to_string().to_lowercase(),
-                account_id: contact.account_id,
+            Some(_) => {
+                ContractError::RequestAlreadyExists.panic()
             }
         }
     }
 
+    /// Applies normalization rules and validator of the contact category, the category has to be registered and not deprecated.
+    fn prepare_contact(&self, contact: Contact) -> Contact {
+        require(!contact.value.is_empty(), ContractError::ContactValueEmpty);
+
+        let category = self.get_active_category(&contact.category);
+        if category.requires_stable_id {
+            require(contact.account_id.is_some(), ContractError::ContactAccountIdMissing);
+        }
+
+        let original_value = contact.value.trim().to_string();
+        let value = self.normalize_value(&category, contact.value);
+        if let Some(Err(reason)) = category.validator.map(|validator| validator.validate(&value)) {
+            ContractError::ContactValueInvalid.panic_with(reason.code())
+        }
+
+        Contact {
+            display_value: if original_value.to_lowercase() != value { Some(original_value) } else { None },
+            value,
+            ..contact
+        }
+    }
+
+    /// Contact can't be bound if it or a contact with the same skeleton is already bound.
+    /// Contacts of version 1 are indexed by saved values, so nothing is bound until they are moved.
+    fn assert_contact_available(&self, contact: &Contact) {
+        require(self.legacy_accounts.is_empty(), ContractError::MigrationInProgress);
+
+        let contact_owner = self.get_account_for_contact(contact.clone());
+        require(contact_owner.is_none(), ContractError::ContactAlreadyRegistered);
+
+        if let Some(contact_skeleton) = self.get_contact_skeleton(contact) {
+            require(!self.contact_skeletons.contains_key(&contact_skeleton), ContractError::ContactConfusable);
+        }
+    }
+
+    /// UTS #39 skeleton of the contact value, contacts identified by stable id don't have it.
+    fn get_contact_skeleton(&self, contact: &Contact) -> Option<String> {
+        if self.get_stable_id(contact).is_some() {
+            None
+        } else {
+            Some(format!("{}:{}", contact.category, unicode_security::skeleton(&contact.value).collect::<String>()))
+        }
+    }
+
+    /// Applies normalization rules of the registered category without validation, used to look up bound contacts.
+    fn normalize_contact(&self, contact: Contact) -> Contact {
+        match self.categories.get(&contact.category) {
+            Some(category) => Contact {
+                value: self.normalize_value(&category, contact.value),
+                display_value: None,
+                ..contact
+            },
+            None => contact
+        }
+    }
+
+    fn normalize_value(&self, category: &Category, value: String) -> String {
+        category.normalization_rules.iter().fold(value, |value, rule| rule.apply(value, &self.email_domain_rules))
+    }
+
+    /// Sets the contact of the whitelisted request. With `is_private` the contact is bound as a salted hash,
+    /// default of the category is used if it's not set. Until the request is confirmed or removed
+    /// its contact is stored as is and returned by `get_request`, private or not.
     #[payable]
-    pub fn start_auth(&mut self, request_key: RequestKey, contact: Contact) {
+    pub fn start_auth(&mut self, request_key: RequestKey, contact: Contact, is_private: Option<bool>) {
         assert_one_yocto();
 
         let account_id: AccountId = env::predecessor_account_id();
 
-        let prepared_contact = Contract::prepare_contact(contact);
+        let prepared_contact = self.prepare_contact(contact);
 
-        let contact_owner = self.get_account_for_contact(prepared_contact.clone());
-        assert!(contact_owner.is_none(), "Contact already registered");
+        self.assert_contact_available(&prepared_contact);
 
         match self.get_request(request_key.clone()) {
             Some(request) => {
-                assert_eq!(
-                    request.account_id,
-                    account_id,
-                    "Key whitelisted for different account"
-                );
+                require(request.account_id == account_id, ContractError::RequestOfDifferentAccount);
+                require(!request.is_expired(), ContractError::RequestExpired);
+                require(prepared_contact.category == request.category, ContractError::ContactCategoryMismatch);
+                if let Some(contact_hash) = &request.contact_hash {
+                    require(
+                        &self.get_contact_stringified_hash(prepared_contact.clone()) == contact_hash,
+                        ContractError::ContactNotWhitelisted,
+                    );
+                }
 
                 match request.contact {
                     None => {
+                        Event::RequestStarted(vec![RequestData {
+                            account_id: account_id.clone(),
+                            request_key: request_key.clone(),
+                            category: request.category.clone(),
+                        }]).emit();
+
                         self.requests.insert(
                             &request_key,
                             &Request {
                                 contact: Some(prepared_contact),
-                                account_id,
+                                is_private: is_private.unwrap_or_else(|| self.is_private_by_default(&request.category)),
+                                ..request
                             },
                         );
                     }
                     Some(_) =>
-                        env::panic(b"Contact already exists for this request")
+                        ContractError::RequestAlreadyStarted.panic()
                 }
             }
-            None => env::panic(b"Only whitelisted keys allowed")
+            None => ContractError::RequestNotWhitelisted.panic()
         }
     }
 
-    pub fn confirm_auth(&mut self, key: SecretKey) {
+    /// Confirms the request with the secret key. Returns false if key doesn't match any request,
+    /// account is locked after `MAX_FAILED_CONFIRMATIONS` misses within `FAILED_CONFIRMATIONS_WINDOW`.
+    /// Misses are counted only for accounts with pending requests.
+    pub fn confirm_auth(&mut self, key: SecretKey) -> bool {
         let account_id = env::predecessor_account_id();
-        let request_key = Contract::get_sha256(key);
+        require(self.get_remaining_attempts(account_id.clone()) > 0, ContractError::TooManyFailedAttempts);
+
+        let request_key = Contract::get_request_key_hash(&account_id, key);
 
-        match Contract::get_request(self, request_key.clone()) {
+        match self.requests.get(&request_key) {
             Some(request) => {
-                assert_eq!(
-                    account_id,
-                    request.account_id,
-                    "No access to confirm this request"
-                );
+                require(account_id == request.account_id, ContractError::RequestOfDifferentAccount);
+                require(!request.is_expired(), ContractError::RequestExpired);
 
                 match request.contact {
                     Some(requested_contact) => {
-                        self.requests.remove(&request_key).expect("Unexpected request");
+                        require(requested_contact.category == request.category, ContractError::ContactCategoryMismatch);
+                        self.remove_request_by_key(&request_key).expect("Unexpected request");
+                        Event::RequestConfirmed(vec![RequestData {
+                            account_id: account_id.clone(),
+                            request_key,
+                            category: request.category,
+                        }]).emit();
 
                         let initial_storage_usage = env::storage_usage();
+                        let record = ContactRecord::new(requested_contact, request.whitelisted_by, VerificationMethod::SecretKey, request.is_private);
+                        self.bind_contact(account_id.clone(), record, initial_storage_usage, WHITELIST_STORAGE_COST);
 
-                        let mut contacts = self.get_contacts(account_id.clone()).unwrap_or(vec![]);
-                        contacts.push(requested_contact.clone());
-
-                        self.insert_accounts_for_contact(account_id.clone(), requested_contact);
-
-                        self.accounts.insert(&account_id, &contacts);
-
-                        // update storage
-                        let tokens_per_entry_in_bytes = env::storage_usage() - initial_storage_usage;
-                        let tokens_per_entry_storage_price: Balance = Balance::from(tokens_per_entry_in_bytes) * STORAGE_PRICE_PER_BYTE;
-                        let storage_paid = Contract::storage_paid(self, ValidAccountId::try_from(account_id.clone()).unwrap());
-
-                        assert!(
-                            storage_paid.0 >= tokens_per_entry_storage_price,
-                            "{} requires minimum storage of {}", account_id, tokens_per_entry_storage_price
-                        );
-
-                        let balance: Balance = storage_paid.0 + WHITELIST_STORAGE_COST - WHITELIST_FEE - tokens_per_entry_storage_price;
-                        self.storage_deposits.insert(&account_id, &balance);
-
-                        env::log(format!("@{} spent {} yNEAR for storage", account_id, tokens_per_entry_storage_price).as_bytes());
+                        self.failed_confirmations.remove(&account_id);
+                        true
                     }
                     None =>
-                        env::panic(b"Confirm of undefined contact")
+                        ContractError::RequestContactMissing.panic()
                 }
             }
+            None if self.get_request_keys(account_id.clone()).is_empty() =>
+                // nothing to guess, failed attempt isn't saved for accounts without requests
+                false,
             None => {
-                env::panic(b"Request not found");
+                // not a panic, failed attempt has to be saved
+                self.add_failed_confirmation(account_id.clone());
+                Event::ConfirmFailed(vec![ConfirmFailedData {
+                    account_id: account_id.clone(),
+                    remaining_attempts: self.get_remaining_attempts(account_id),
+                }]).emit();
+                false
             }
         }
     }
 
-    fn get_sha256(key: SecretKey) -> String {
-        digest(key)
+    /// Verifier which whitelisted the OTP request binds its contact after user gave it the right code.
+    pub fn confirm_otp(&mut self, request_key: RequestKey) {
+        let request = self.get_otp_request(&request_key);
+
+        self.remove_request_by_key(&request_key);
+        Event::RequestConfirmed(vec![RequestData {
+            account_id: request.account_id.clone(),
+            request_key,
+            category: request.category,
+        }]).emit();
+
+        let initial_storage_usage = env::storage_usage();
+        let contact = request.contact.unwrap_or_else(|| ContractError::RequestContactMissing.panic());
+        let record = ContactRecord::new(contact, request.whitelisted_by, VerificationMethod::Otp, request.is_private);
+        self.bind_contact(request.account_id, record, initial_storage_usage, WHITELIST_STORAGE_COST);
     }
 
-    pub(crate) fn are_contacts_equal(contact1: Contact, contact2: Contact) -> bool {
-        if contact1.category == ContactCategories::Telegram && contact2.category == ContactCategories::Telegram {
-            contact1.account_id == contact2.account_id
+    /// Verifier which whitelisted the OTP request reports a wrong code, request is removed after `OTP_MAX_ATTEMPTS`.
+    pub fn reject_otp(&mut self, request_key: RequestKey) {
+        let request = self.get_otp_request(&request_key);
+        let account_id = request.account_id.clone();
+        let attempts = request.otp.as_ref().map_or(0, |otp| otp.attempts) + 1;
+
+        if attempts >= OTP_MAX_ATTEMPTS {
+            self.remove_request_by_key(&request_key);
+            let whitelist_storage_cost = self.refund_request(&account_id, false);
+            Event::RequestRemoved(vec![RequestRemovedData {
+                account_id,
+                request_key,
+                category: request.category,
+                reason: RequestRemovedReason::OtpAttempts,
+                refund: U128(whitelist_storage_cost),
+            }]).emit();
         } else {
-            contact1.category == contact2.category && contact1.value == contact2.value
+            self.requests.insert(&request_key, &Request {
+                otp: Some(Otp { attempts }),
+                ..request
+            });
+            Event::ConfirmFailed(vec![ConfirmFailedData {
+                account_id,
+                remaining_attempts: u32::from(OTP_MAX_ATTEMPTS - attempts),
+            }]).emit();
         }
     }
 
-    // TODO only first N chars of category to reduce storage?
-    fn get_contact_stringified(contact: Contact) -> String {
-        if contact.category == ContactCategories::Telegram {
-            format!("{:?}:{:?}", contact.category, contact.account_id.unwrap())
-        } else {
-            format!("{:?}:{}", contact.category, contact.value)
-        }
+    /// Pending OTP request whitelisted by the caller.
+    fn get_otp_request(&self, request_key: &RequestKey) -> Request {
+        let request = self.requests.get(request_key).unwrap_or_else(|| ContractError::RequestNotFound.panic());
+        require(request.otp.is_some(), ContractError::RequestWithoutOtp);
+        require(request.whitelisted_by == env::predecessor_account_id(), ContractError::NoAccess);
+        require(!request.is_expired(), ContractError::RequestExpired);
+        request
     }
 
-    pub(crate) fn insert_accounts_for_contact(&mut self, account_id: AccountId, contact: Contact) {
-        let contact_stringified = Contract::get_contact_stringified(contact);
-        self.accounts_for_contacts.insert(&contact_stringified, &account_id);
+    fn add_failed_confirmation(&mut self, account_id: AccountId) {
+        let failed_confirmations = match self.failed_confirmations.get(&account_id) {
+            Some(failed_confirmations) if !failed_confirmations.is_window_expired() => FailedConfirmations {
+                count: failed_confirmations.count + 1,
+                window_start: failed_confirmations.window_start,
+            },
+            _ => FailedConfirmations {
+                count: 1,
+                window_start: env::block_timestamp(),
+            }
+        };
+        self.failed_confirmations.insert(&account_id, &failed_confirmations);
     }
 
-    pub(crate) fn remove_accounts_for_contact(&mut self, contact: Contact) {
-        let contact_stringified = Contract::get_contact_stringified(contact);
-        self.accounts_for_contacts.remove(&contact_stringified);
+    pub fn get_remaining_attempts(&self, account_id: AccountId) -> u32 {
+        match self.failed_confirmations.get(&account_id) {
+            Some(failed_confirmations) if !failed_confirmations.is_window_expired() =>
+                MAX_FAILED_CONFIRMATIONS.saturating_sub(failed_confirmations.count),
+            _ => MAX_FAILED_CONFIRMATIONS
+        }
     }
 
-    pub fn get_request(&self, request_key: RequestKey) -> Option<Request> {
-        match self.requests.get(&request_key) {
-            Some(request) => Some(request),
-            None => None
-        }
+    /// Binds contact to the account with a single transaction using attestation signed by one of the verifier keys.
+    /// Signature is made over Borsh serialized `ClaimMessage`, used nonces are rejected.
+    /// Private mode is chosen by the user and isn't a part of the attestation.
+    pub fn claim_contact(&mut self, contact: Contact, expires_at: U64, nonce: U64, signature: Base64VecU8, is_private: Option<bool>) {
+        let account_id = env::predecessor_account_id();
+
+        require(env::block_timestamp() < expires_at.0, ContractError::SignatureExpired);
+        require(!self.used_nonces.contains(&nonce.0), ContractError::NonceAlreadyUsed);
+
+        let prepared_contact = self.prepare_contact(contact);
+
+        self.assert_contact_available(&prepared_contact);
+
+        let message = ClaimMessage {
+            account_id: account_id.clone(),
+            contact: self.get_contact_stringified(prepared_contact.clone()),
+            value: prepared_contact.value.clone(),
+            contract_id: env::current_account_id(),
+            expires_at: expires_at.0,
+            nonce: nonce.0,
+        }.try_to_vec().unwrap();
+        let verifier_key = self.get_verifier_key_of_signature(&message, &signature.0, &prepared_contact.category)
+            .unwrap_or_else(|| ContractError::InvalidSignature.panic());
+
+        let initial_storage_usage = env::storage_usage();
+        self.used_nonces.insert(&nonce.0);
+        let is_private = is_private.unwrap_or_else(|| self.is_private_by_default(&prepared_contact.category));
+        let record = ContactRecord::new(prepared_contact, String::from(&verifier_key), VerificationMethod::Attestation, is_private);
+        self.bind_contact(account_id, record, initial_storage_usage, 0);
     }
 
-    pub fn get_request_key(&self, account_id: AccountId) -> Option<RequestKey> {
-        self.requests
-            .iter()
-            .find_map(|(key, request)| if request.account_id == account_id { Some(key) } else { None })
+    /// Only keys allowed to attest contacts of the category are checked.
+    fn get_verifier_key_of_signature(&self, message: &[u8], signature: &[u8], category: &CategoryId) -> Option<Base58PublicKey> {
+        let signature = ed25519_dalek::Signature::try_from(signature).ok()?;
+
+        self.verifier_keys.iter()
+            .filter(|(_, categories)| categories.contains(category))
+            .map(|(verifier_key, _)| verifier_key)
+            .find(|verifier_key| {
+                // first byte of the key is a curve type
+                match ed25519_dalek::PublicKey::from_bytes(&verifier_key.0[1..]) {
+                    Ok(public_key) => public_key.verify(message, &signature).is_ok(),
+                    Err(_) => false
+                }
+            })
     }
 
-    pub fn remove_request(&mut self) {
-        let account_id = env::predecessor_account_id();
+    /// Adds contact to the account and charges storage used since `initial_storage_usage` plus whitelist fee.
+    /// `storage_reserved` is a part of the storage deposit which was already taken for the request.
+    /// Private contact is saved as a salted hash only.
+    pub(crate) fn bind_contact(&mut self, account_id: AccountId, mut record: ContactRecord, initial_storage_usage: u64, storage_reserved: Balance) {
+        // contact could be bound by another account after the request was started
+        self.assert_contact_available(&record.contact);
 
-        match Contract::get_request_key(self, account_id.clone()) {
-            Some(request_key) => {
-                self.requests.remove(&request_key);
+        if record.is_private {
+            record.contact = self.get_private_contact(&record.contact);
+        }
+        let contact = record.contact.clone();
+        let is_private = record.is_private;
+        let mut contacts = self.get_contacts(account_id.clone()).unwrap_or_default();
+        contacts.push(record);
+
+        if is_private {
+            // skeleton would reveal the value
+            self.accounts_for_contacts.insert(&self.get_contact_stringified(contact.clone()), &account_id);
+        } else {
+            self.insert_accounts_for_contact(account_id.clone(), contact.clone());
+        }
 
-                // update storage
-                let storage_paid = Contract::storage_paid(self, ValidAccountId::try_from(account_id.clone()).unwrap());
-                let whitelist_storage_cost = WHITELIST_STORAGE_COST - WHITELIST_FEE;
-                let balance: Balance = storage_paid.0 + whitelist_storage_cost;
-                self.storage_deposits.insert(&account_id, &balance);
+        self.save_contacts(&account_id, contacts);
 
-                env::log(format!("@{} removed previous request for {} yNEAR", account_id, whitelist_storage_cost).as_bytes());
-            }
-            None => {
-                env::panic(b"Request not found")
-            }
-        }
+        // update storage
+        let tokens_per_entry_in_bytes = env::storage_usage() - initial_storage_usage;
+        let tokens_per_entry_storage_price: Balance = Balance::from(tokens_per_entry_in_bytes) * STORAGE_PRICE_PER_BYTE;
+        let storage_paid = Contract::storage_paid(self, ValidAccountId::try_from(account_id.clone()).unwrap());
+        let storage_required = (tokens_per_entry_storage_price + WHITELIST_FEE).saturating_sub(storage_reserved);
+
+        require(storage_paid.0 >= storage_required, ContractError::NotEnoughStorageDeposit);
+
+        let balance: Balance = storage_paid.0 + storage_reserved - WHITELIST_FEE - tokens_per_entry_storage_price;
+        self.storage_deposits.insert(&account_id, &balance);
+
+        Event::ContactBound(vec![ContactData {
+            account_id,
+            contacts: vec![contact],
+            storage: U128(tokens_per_entry_storage_price),
+        }]).emit();
     }
 
-    pub fn get_contacts(&self, account_id: AccountId) -> Option<Vec<Contact>> {
-        match self.accounts.get(&account_id) {
-            Some(contacts) => Some(contacts),
-            None => None
+    /// Allows the key to sign attestations for the given categories, replacing previous ones.
+    pub fn add_verifier_key(&mut self, public_key: Base58PublicKey, categories: Vec<CategoryId>) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
+        require(public_key.0.len() == 33 && public_key.0[0] == CurveType::ED25519 as u8, ContractError::UnsupportedKeyType);
+        require(!categories.is_empty(), ContractError::CategoriesEmpty);
+        for category_id in categories.iter() {
+            self.get_active_category(category_id);
         }
+
+        self.verifier_keys.insert(&public_key, &categories);
+
+        Event::VerifierKeyAdded(vec![VerifierKeyData {
+            public_key,
+            categories: Some(categories),
+        }]).emit();
     }
 
-    pub fn get_account_for_contact(&self, contact: Contact) -> Option<AccountId> {
-        let contact_stringified = Contract::get_contact_stringified(contact);
-        self.get_account_for_contact_stringified(contact_stringified)
+    pub fn remove_verifier_key(&mut self, public_key: Base58PublicKey) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
+
+        require(self.verifier_keys.remove(&public_key).is_some(), ContractError::VerifierKeyNotFound);
+
+        Event::VerifierKeyRemoved(vec![VerifierKeyData {
+            public_key,
+            categories: None,
+        }]).emit();
     }
 
-    pub fn get_account_for_contact_stringified(&self, contact_stringified: ContactStringified) -> Option<AccountId> {
-        match self.accounts_for_contacts.get(&contact_stringified) {
-            Some(account_id) => Some(account_id),
-            None => None
-        }
+    pub fn get_verifier_keys(&self) -> HashMap<String, Vec<CategoryId>> {
+        self.verifier_keys.iter().map(|(public_key, categories)| (String::from(&public_key), categories)).collect()
     }
 
-    pub fn get_contacts_by_type(&self, account_id: AccountId, category: ContactCategories) -> Option<Vec<String>> {
-        match self.accounts.get(&account_id) {
-            Some(contacts) =>
-                {
-                    let filtered_contacts: Vec<String> = contacts
-                        .into_iter()
-                        .filter(|contact| contact.category == category)
-                        .map(|contact| contact.value)
-                        .collect();
-                    Some(filtered_contacts)
-                }
-            None => None
+    /// Grants verifier rights for the given categories, replacing previous ones.
+    pub fn add_verifier(&mut self, account_id: ValidAccountId, categories: Vec<CategoryId>) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
+        require(!categories.is_empty(), ContractError::CategoriesEmpty);
+        for category_id in categories.iter() {
+            self.get_active_category(category_id);
         }
+
+        self.verifiers.insert(account_id.as_ref(), &categories);
+
+        Event::VerifierAdded(vec![VerifierData {
+            account_id: account_id.into(),
+            categories: Some(categories),
+        }]).emit();
     }
 
-    pub fn has_request_key(&self, account_id: AccountId) -> bool {
-        self.get_request_key(account_id) != None
+    pub fn remove_verifier(&mut self, account_id: ValidAccountId) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
+
+        require(self.verifiers.remove(account_id.as_ref()).is_some(), ContractError::VerifierNotFound);
+
+        Event::VerifierRemoved(vec![VerifierData {
+            account_id: account_id.into(),
+            categories: None,
+        }]).emit();
     }
 
+    pub fn get_verifiers(&self) -> HashMap<AccountId, Vec<CategoryId>> {
+        self.verifiers.iter().collect()
+    }
 
-    #[payable]
-    pub fn send(&mut self, contact: Contact) -> Promise {
-        let tokens: Balance = near_sdk::env::attached_deposit();
+    pub fn is_verifier(&self, account_id: AccountId, category: CategoryId) -> bool {
+        match self.verifiers.get(&account_id) {
+            Some(categories) => categories.contains(&category),
+            None => false
+        }
+    }
+
+    /// Request keys are namespaced by account, so the same secret gives different keys for different accounts.
+    fn get_request_key_hash(account_id: &str, key: SecretKey) -> RequestKey {
+        digest(format!("{}:{}", account_id, key))
+    }
 
-        let recipient = self.get_account_for_contact(contact);
-        assert!(!recipient.is_none(), "Contact not found");
+    /// Master account registers a new contact category.
+    pub fn add_category(&mut self, category_id: CategoryId, name: String, requires_stable_id: bool, normalization_rules: Vec<NormalizationRule>, validator: Option<ContactValidator>) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
+        require(!category_id.is_empty() && !category_id.contains(':'), ContractError::CategoryIdInvalid);
+        require(self.categories.get(&category_id).is_none(), ContractError::CategoryAlreadyExists);
+
+        let category = Category {
+            name,
+            requires_stable_id,
+            normalization_rules,
+            validator,
+            deprecated: false,
+            max_verification_age: None,
+            private_by_default: false,
+        };
+        self.categories.insert(&category_id, &category);
+
+        Event::CategoryAdded(vec![CategoryData { category_id, category }]).emit();
+    }
 
-        let recipient_account_id = recipient.unwrap();
+    /// Master account deprecates the category, new requests and verifiers of the category are rejected.
+    /// Bound contacts and already whitelisted requests are kept.
+    pub fn deprecate_category(&mut self, category_id: CategoryId) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
 
-        env::log(format!("Tokens sent to @{}", recipient_account_id).as_bytes());
+        let category = Category {
+            deprecated: true,
+            ..self.get_active_category(&category_id)
+        };
+        self.categories.insert(&category_id, &category);
 
-        Promise::new(recipient_account_id).transfer(tokens)
+        Event::CategoryDeprecated(vec![CategoryData { category_id, category }]).emit();
     }
 
-    pub fn get_all_accounts_for_contacts(&self, from_index: u64, limit: u64) -> HashMap<ContactStringified, AccountId> {
-        let keys = self.accounts_for_contacts.keys_as_vector();
+    /// Master account sets the time in nanoseconds after which bound contacts of the category have to be verified again.
+    /// `None` keeps contacts verified forever.
+    pub fn set_max_verification_age(&mut self, category_id: CategoryId, max_verification_age: Option<U64>) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
 
-        (from_index..std::cmp::min(from_index + limit, keys.len()))
-            .map(|index| {
-                let contact_stringified = keys.get(index).unwrap();
-                let account_id = self.get_account_for_contact_stringified(contact_stringified.clone()).unwrap();
-                (contact_stringified, account_id)
-            })
-            .collect()
+        let category = Category {
+            max_verification_age: max_verification_age.map(|age| age.0),
+            ..self.categories.get(&category_id).unwrap_or_else(|| ContractError::CategoryNotFound.panic())
+        };
+        self.categories.insert(&category_id, &category);
+
+        Event::CategoryUpdated(vec![CategoryData { category_id, category }]).emit();
     }
 
-    pub fn get_all_contacts(&self, from_index: u64, limit: u64) -> HashMap<AccountId, Vec<Contact>> {
-        let keys = self.accounts.keys_as_vector();
+    /// Master account replaces normalization rules of the category.
+    /// Contacts bound before the change keep their normalized values.
+    pub fn set_normalization_rules(&mut self, category_id: CategoryId, normalization_rules: Vec<NormalizationRule>) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
 
-        (from_index..std::cmp::min(from_index + limit, keys.len()))
-            .map(|index| {
-                let account_id = keys.get(index).unwrap();
-                let all_contacts = self.get_contacts(account_id.clone()).unwrap();
-                (account_id, all_contacts)
-            })
-            .collect()
+        let category = Category {
+            normalization_rules,
+            ..self.categories.get(&category_id).unwrap_or_else(|| ContractError::CategoryNotFound.panic())
+        };
+        self.categories.insert(&category_id, &category);
+
+        Event::CategoryUpdated(vec![CategoryData { category_id, category }]).emit();
     }
 
-    pub fn get_all_contacts_by_type(&self, category: ContactCategories, from_index: u64, limit: u64) -> HashMap<AccountId, Vec<String>> {
-        assert!(limit <= 100, "Abort. Limit > 100");
+    /// Master account chooses whether contacts of the category are bound as private when user doesn't choose.
+    pub fn set_private_by_default(&mut self, category_id: CategoryId, private_by_default: bool) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
 
-        let keys = self.accounts.keys_as_vector();
+        let category = Category {
+            private_by_default,
+            ..self.categories.get(&category_id).unwrap_or_else(|| ContractError::CategoryNotFound.panic())
+        };
+        self.categories.insert(&category_id, &category);
 
-        (from_index..std::cmp::min(from_index + limit, keys.len()))
-            .map(|index| {
-                let account_id = keys.get(index).unwrap();
-                let all_contacts = self.get_contacts_by_type(account_id.clone(), category.clone()).unwrap();
-                (account_id, all_contacts)
-            })
-            .filter(|(_k, v)| !v.is_empty())
-            .map(|(k, v)| (k, v))
-            .collect()
+        Event::CategoryUpdated(vec![CategoryData { category_id, category }]).emit();
     }
 
-    pub fn get_owners(&self, _contact: Contact) -> Vec<String> {
-        panic!("Deprecated. Use `get_account_for_contact` instead");
+    fn is_private_by_default(&self, category_id: &CategoryId) -> bool {
+        self.categories.get(category_id).is_some_and(|category| category.private_by_default)
     }
 
-    pub fn is_owner(&self, account_id: AccountId, contact: Contact) -> bool {
-        match self.accounts.get(&account_id) {
-            Some(contacts) =>
-                {
-                    contacts.into_iter()
-                        .any(|_contact| Contract::are_contacts_equal(_contact, contact.clone()))
-                }
-            None => false
-        }
+    pub fn get_category(&self, category_id: CategoryId) -> Option<Category> {
+        self.categories.get(&category_id)
     }
 
-    // remove contact
-    pub fn remove(&mut self, contact: Contact) -> bool {
-        let account_id = env::predecessor_account_id();
-        let is_owner = Contract::is_owner(self, account_id.clone(), contact.clone());
+    pub fn get_categories(&self) -> HashMap<CategoryId, Category> {
+        self.categories.iter().collect()
+    }
 
-        assert!(is_owner, "Not an owner of this contact");
+    /// Master account sets canonicalization rule of the email provider domain, the domain is in punycode.
+    /// Contacts bound before the change keep their normalized values.
+    pub fn set_email_domain_rule(&mut self, domain: String, rule: EmailDomainRule) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
 
-        match self.accounts.get(&account_id) {
-            Some(contacts) =>
-                {
-                    let initial_storage_usage = env::storage_usage();
+        self.email_domain_rules.insert(&domain, &rule);
 
-                    let filtered_contacts: Vec<Contact> = contacts
-                        .into_iter()
-                        .filter(|_contact| !Contract::are_contacts_equal(_contact.clone(), contact.clone()))
-                        .collect();
-                    self.accounts.insert(&account_id, &filtered_contacts);
+        Event::EmailDomainRuleSet(vec![EmailDomainRuleData { domain, rule: Some(rule) }]).emit();
+    }
 
-                    self.remove_accounts_for_contact(contact);
+    pub fn remove_email_domain_rule(&mut self, domain: String) {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
 
-                    let tokens_per_entry_in_bytes = initial_storage_usage - env::storage_usage();
-                    let tokens_per_entry_storage_price: Balance = Balance::from(tokens_per_entry_in_bytes) * STORAGE_PRICE_PER_BYTE;
+        require(self.email_domain_rules.remove(&domain).is_some(), ContractError::EmailDomainRuleNotFound);
+
+        Event::EmailDomainRuleRemoved(vec![EmailDomainRuleData { domain, rule: None }]).emit();
+    }
+
+    pub fn get_email_domain_rules(&self) -> HashMap<String, EmailDomainRule> {
+        self.email_domain_rules.iter().collect()
+    }
+
+    fn get_default_email_domain_rules() -> UnorderedMap<String, EmailDomainRule> {
+        let mut email_domain_rules = UnorderedMap::new(StorageKey::EmailDomainRules.try_to_vec().unwrap());
+        for (domain, rule) in EmailDomainRule::defaults() {
+            email_domain_rules.insert(&domain, &rule);
+        }
+        email_domain_rules
+    }
+
+    fn get_active_category(&self, category_id: &CategoryId) -> Category {
+        let category = self.categories.get(category_id).unwrap_or_else(|| ContractError::CategoryNotFound.panic());
+        require(!category.deprecated, ContractError::CategoryDeprecated);
+        category
+    }
+
+    fn requires_stable_id(&self, category_id: &CategoryId) -> bool {
+        self.categories.get(category_id).is_some_and(|category| category.requires_stable_id)
+    }
+
+    /// Identity of the contact for categories with stable id. Contacts bound before the category
+    /// started to require it are identified by value until `set_contact_stable_id`.
+    fn get_stable_id(&self, contact: &Contact) -> Option<u64> {
+        if self.requires_stable_id(&contact.category) {
+            contact.account_id.map(|stable_id| stable_id.0)
+        } else {
+            None
+        }
+    }
+
+    pub(crate) fn are_contacts_equal(&self, contact1: Contact, contact2: Contact) -> bool {
+        if contact1.category != contact2.category {
+            return false;
+        }
+        match (self.get_stable_id(&contact1), self.get_stable_id(&contact2)) {
+            (Some(stable_id1), Some(stable_id2)) => stable_id1 == stable_id2,
+            _ => contact1.value == contact2.value
+        }
+    }
+
+    // TODO only first N chars of category to reduce storage?
+    fn get_contact_stringified(&self, contact: Contact) -> String {
+        match self.get_stable_id(&contact) {
+            Some(stable_id) => format!("{}:{}", contact.category, stable_id),
+            None => format!("{}:{}", contact.category, contact.value)
+        }
+    }
+
+    fn get_contact_stringified_hash(&self, contact: Contact) -> String {
+        digest(self.get_contact_stringified(contact))
+    }
+
+    /// Hash of the normalized contact which verifier passes to `whitelist_key`.
+    pub fn get_contact_hash(&self, contact: Contact) -> String {
+        self.get_contact_stringified_hash(self.prepare_contact(contact))
+    }
+
+    /// Verifier of the category moves the contact bound by value to its stable id, e.g. GitHub handle to the numeric user id.
+    /// Value is kept as display data.
+    pub fn set_contact_stable_id(&mut self, account_id: ValidAccountId, category: CategoryId, value: String, stable_id: U64) {
+        require(self.is_verifier(env::predecessor_account_id(), category.clone()), ContractError::NoAccess);
+        require(self.requires_stable_id(&category), ContractError::CategoryWithoutStableId);
+
+        let account_id: AccountId = account_id.into();
+        let contact = self.normalize_contact(Contact {
+            category,
+            value,
+            account_id: None,
+            display_value: None,
+        });
+        let mut contacts = self.get_contacts(account_id.clone()).unwrap_or_default();
+        let contact_index = contacts.iter()
+            .map(|record| &record.contact)
+            .position(|_contact| _contact.category == contact.category && _contact.account_id.is_none() && _contact.value == contact.value)
+            .unwrap_or_else(|| ContractError::ContactNotFound.panic());
+
+        let stable_contact = Contact {
+            account_id: Some(stable_id),
+            ..contacts[contact_index].contact.clone()
+        };
+        require(self.get_account_for_contact(stable_contact.clone()).is_none(), ContractError::ContactAlreadyRegistered);
+
+        self.remove_accounts_for_contact(contacts[contact_index].contact.clone());
+        self.insert_accounts_for_contact(account_id.clone(), stable_contact.clone());
+        contacts[contact_index].contact = stable_contact.clone();
+        self.save_contacts(&account_id, contacts);
+
+        Event::ContactStableIdSet(vec![ContactStableIdData {
+            account_id,
+            contact: stable_contact,
+        }]).emit();
+    }
+
+    /// Verifier of the category refreshes the handle of the contact identified by stable id, e.g. renamed Telegram username.
+    /// Contact stays bound to the same key, storage difference is taken from or returned to the storage deposit.
+    pub fn update_contact_display(&mut self, account_id: ValidAccountId, category: CategoryId, stable_id: U64, new_value: String) {
+        require(self.is_verifier(env::predecessor_account_id(), category.clone()), ContractError::NoAccess);
+        require(self.requires_stable_id(&category), ContractError::CategoryWithoutStableId);
+
+        let account_id: AccountId = account_id.into();
+        let updated_contact = self.prepare_contact(Contact {
+            category,
+            value: new_value,
+            account_id: Some(stable_id),
+            display_value: None,
+        });
+        let mut contacts = self.get_contacts(account_id.clone()).unwrap_or_default();
+        let contact_index = contacts.iter()
+            .map(|record| &record.contact)
+            .position(|contact| self.get_stable_id(contact).is_some() && self.are_contacts_equal(contact.clone(), updated_contact.clone()))
+            .unwrap_or_else(|| ContractError::ContactNotFound.panic());
+        let previous_value = contacts[contact_index].contact.value.clone();
+
+        let initial_storage_usage = env::storage_usage();
+        contacts[contact_index].contact = updated_contact.clone();
+        self.save_contacts(&account_id, contacts);
+
+        self.update_storage_deposit(&account_id, initial_storage_usage);
+
+        Event::ContactDisplayUpdated(vec![ContactDisplayData {
+            account_id,
+            contact: updated_contact,
+            previous_value,
+        }]).emit();
+    }
+
+    /// Verifier of the category confirms the bound contact again, `verified_at` is refreshed and the contact stays bound.
+    pub fn reverify_contact(&mut self, account_id: ValidAccountId, contact: Contact) {
+        let verifier_id = env::predecessor_account_id();
+        require(self.is_verifier(verifier_id.clone(), contact.category.clone()), ContractError::NoAccess);
+
+        let account_id: AccountId = account_id.into();
+        let contact = self.normalize_contact(contact);
+        let mut contacts = self.get_contacts(account_id.clone()).unwrap_or_default();
+        let contact_index = contacts.iter()
+            .position(|record| self.is_record_of_contact(record, &contact))
+            .unwrap_or_else(|| ContractError::ContactNotFound.panic());
+
+        let initial_storage_usage = env::storage_usage();
+        contacts[contact_index].verified_at = env::block_timestamp();
+        contacts[contact_index].verified_by = verifier_id.clone();
+        contacts[contact_index].verification_method = VerificationMethod::Reverification;
+        let reverified_contact = contacts[contact_index].contact.clone();
+        self.save_contacts(&account_id, contacts);
+
+        self.update_storage_deposit(&account_id, initial_storage_usage);
+
+        Event::ContactReverified(vec![ContactReverifiedData {
+            account_id,
+            contact: reverified_contact,
+            verified_at: U64(env::block_timestamp()),
+            verified_by: verifier_id,
+        }]).emit();
+    }
+
+    /// Takes storage used since `initial_storage_usage` from the storage deposit, or returns released storage to it.
+    fn update_storage_deposit(&mut self, account_id: &AccountId, initial_storage_usage: u64) {
+        let storage_paid = self.storage_deposits.get(account_id).unwrap_or(0);
+        let balance = if env::storage_usage() >= initial_storage_usage {
+            let storage_price = Balance::from(env::storage_usage() - initial_storage_usage) * STORAGE_PRICE_PER_BYTE;
+            require(storage_paid >= storage_price, ContractError::NotEnoughStorageDeposit);
+            storage_paid - storage_price
+        } else {
+            storage_paid + Balance::from(initial_storage_usage - env::storage_usage()) * STORAGE_PRICE_PER_BYTE
+        };
+        self.storage_deposits.insert(account_id, &balance);
+    }
+
+    /// Contact is stale when its category limits verification age and the limit has passed since `verified_at`.
+    fn is_contact_stale(&self, record: &ContactRecord) -> bool {
+        match self.categories.get(&record.contact.category).and_then(|category| category.max_verification_age) {
+            Some(max_verification_age) => env::block_timestamp() > record.verified_at.saturating_add(max_verification_age),
+            None => false
+        }
+    }
+
+    fn get_contact_record(&self, account_id: AccountId, contact: &Contact) -> Option<ContactRecord> {
+        self.get_contacts(account_id)?
+            .into_iter()
+            .find(|record| self.is_record_of_contact(record, contact))
+    }
+
+    /// Private records are matched by the salted hash of the normalized contact.
+    fn is_record_of_contact(&self, record: &ContactRecord, contact: &Contact) -> bool {
+        if record.is_private {
+            record.contact == self.get_private_contact(contact)
+        } else {
+            self.are_contacts_equal(record.contact.clone(), contact.clone())
+        }
+    }
+
+    /// Contact saved instead of the private one: value is a sha256 of the salted stringified contact,
+    /// stable id and display value are dropped. Salt is a part of the public state, so it's a plain salted hash
+    /// and not a keyed one: it only keeps the value out of enumeration views and precomputed tables.
+    /// Values from small spaces like phone numbers or numeric ids can be recovered by hashing every candidate.
+    fn get_private_contact(&self, contact: &Contact) -> Contact {
+        Contact {
+            category: contact.category.clone(),
+            value: digest(format!("{}:{}", self.contact_salt, self.get_contact_stringified(contact.clone()))),
+            account_id: None,
+            display_value: None,
+        }
+    }
+
+    pub(crate) fn insert_accounts_for_contact(&mut self, account_id: AccountId, contact: Contact) {
+        let contact_stringified = self.get_contact_stringified(contact.clone());
+        self.accounts_for_contacts.insert(&contact_stringified, &account_id);
+
+        if let Some(contact_skeleton) = self.get_contact_skeleton(&contact) {
+            if !self.contact_skeletons.contains_key(&contact_skeleton) {
+                self.contact_skeletons.insert(&contact_skeleton, &contact_stringified);
+            }
+        }
+    }
+
+    pub(crate) fn remove_accounts_for_contact(&mut self, contact: Contact) {
+        let contact_stringified = self.get_contact_stringified(contact.clone());
+        self.accounts_for_contacts.remove(&contact_stringified);
+
+        // skeleton may belong to another contact bound before skeletons were checked
+        if let Some(contact_skeleton) = self.get_contact_skeleton(&contact) {
+            if self.contact_skeletons.get(&contact_skeleton) == Some(contact_stringified) {
+                self.contact_skeletons.remove(&contact_skeleton);
+            }
+        }
+    }
+
+    pub fn get_request(&self, request_key: RequestKey) -> Option<Request> {
+        self.requests.get(&request_key)
+    }
+
+    pub fn get_request_key(&self, account_id: AccountId, category: CategoryId) -> Option<RequestKey> {
+        self.get_request_keys(account_id)
+            .into_iter()
+            .find(|request_key| self.requests.get(request_key).is_some_and(|request| request.category == category))
+    }
+
+    pub fn get_request_keys(&self, account_id: AccountId) -> Vec<RequestKey> {
+        self.request_keys.get(&account_id).unwrap_or_default()
+    }
+
+    /// Inserts request together with its entry in the account index.
+    pub(crate) fn insert_request(&mut self, request_key: &RequestKey, request: &Request) {
+        self.requests.insert(request_key, request);
+
+        let mut request_keys = self.get_request_keys(request.account_id.clone());
+        request_keys.push(request_key.clone());
+        self.request_keys.insert(&request.account_id, &request_keys);
+    }
+
+    /// Removes request together with its entry in the account index.
+    pub(crate) fn remove_request_by_key(&mut self, request_key: &RequestKey) -> Option<Request> {
+        let request = self.requests.remove(request_key)?;
+
+        let request_keys: Vec<RequestKey> = self.get_request_keys(request.account_id.clone())
+            .into_iter()
+            .filter(|key| key != request_key)
+            .collect();
+        if request_keys.is_empty() {
+            self.request_keys.remove(&request.account_id);
+        } else {
+            self.request_keys.insert(&request.account_id, &request_keys);
+        }
+
+        Some(request)
+    }
+
+    pub fn remove_request(&mut self, category: CategoryId) {
+        let account_id = env::predecessor_account_id();
+
+        match Contract::get_request_key(self, account_id.clone(), category) {
+            Some(request_key) => {
+                let request = self.remove_request_by_key(&request_key).expect("Unexpected request");
+
+                // update storage
+                let whitelist_storage_cost = self.refund_request(&account_id, false);
+
+                Event::RequestRemoved(vec![RequestRemovedData {
+                    account_id,
+                    request_key,
+                    category: request.category,
+                    reason: RequestRemovedReason::User,
+                    refund: U128(whitelist_storage_cost),
+                }]).emit();
+            }
+            None => {
+                ContractError::RequestNotFound.panic()
+            }
+        }
+    }
+
+    /// Removes expired requests in the given range of pending requests and returns
+    /// the whitelist storage cost (minus fee) back to the storage deposits of their owners.
+    /// Anyone can call it. Returns the number of removed requests.
+    pub fn sweep_expired_requests(&mut self, from_index: u64, limit: u64) -> u64 {
+        require(limit <= 100, ContractError::LimitTooHigh);
+
+        let keys = self.requests.keys_as_vector();
+        let expired_request_keys: Vec<RequestKey> = (from_index..std::cmp::min(from_index + limit, keys.len()))
+            .map(|index| keys.get(index).unwrap())
+            .filter(|request_key| self.requests.get(request_key).unwrap().is_expired())
+            .collect();
+
+        for request_key in expired_request_keys.iter() {
+            let request = self.remove_request_by_key(request_key).expect("Unexpected request");
+
+            let whitelist_storage_cost = self.refund_request(&request.account_id, false);
+
+            Event::RequestRemoved(vec![RequestRemovedData {
+                account_id: request.account_id,
+                request_key: request_key.clone(),
+                category: request.category,
+                reason: RequestRemovedReason::Expired,
+                refund: U128(whitelist_storage_cost),
+            }]).emit();
+        }
+
+        expired_request_keys.len() as u64
+    }
+
+    /// Admin method to cancel the request by key or all requests of the account.
+    /// Whitelist fee is kept by contract unless `refund_fee` is set. Returns the number of removed requests.
+    pub fn cancel_request(&mut self, request_key: Option<RequestKey>, account_id: Option<ValidAccountId>, refund_fee: bool) -> u64 {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
+
+        let request_keys: Vec<RequestKey> = match (request_key, account_id) {
+            (Some(request_key), _) => vec![request_key],
+            (None, Some(account_id)) => self.get_request_keys(account_id.into()),
+            (None, None) => ContractError::RequestKeyOrAccountRequired.panic()
+        };
+        require(!request_keys.is_empty(), ContractError::RequestNotFound);
+
+        for request_key in request_keys.iter() {
+            let request = self.remove_request_by_key(request_key).unwrap_or_else(|| ContractError::RequestNotFound.panic());
+
+            let whitelist_storage_cost = self.refund_request(&request.account_id, refund_fee);
+
+            Event::RequestRemoved(vec![RequestRemovedData {
+                account_id: request.account_id,
+                request_key: request_key.clone(),
+                category: request.category,
+                reason: RequestRemovedReason::Admin,
+                refund: U128(whitelist_storage_cost),
+            }]).emit();
+        }
+
+        request_keys.len() as u64
+    }
+
+    /// Returns whitelist storage cost to the storage deposit of removed request owner, fee is returned only if `refund_fee` is set.
+    pub(crate) fn refund_request(&mut self, account_id: &AccountId, refund_fee: bool) -> Balance {
+        let whitelist_storage_cost = if refund_fee { WHITELIST_STORAGE_COST } else { WHITELIST_STORAGE_COST - WHITELIST_FEE };
+        let balance: Balance = self.storage_deposits.get(account_id).unwrap_or(0) + whitelist_storage_cost;
+        self.storage_deposits.insert(account_id, &balance);
+        whitelist_storage_cost
+    }
+
+    /// Contacts of version 1 are converted on read until they are saved again.
+    pub fn get_contacts(&self, account_id: AccountId) -> Option<Vec<ContactRecord>> {
+        match self.accounts.get(&account_id) {
+            Some(records) => Some(records.into_iter().map(ContactRecord::from).collect()),
+            None => self.legacy_accounts.get(&account_id)
+                .map(|contacts| contacts.into_iter().map(|contact| self.get_legacy_record(&account_id, contact)).collect())
+        }
+    }
+
+    /// Contacts of version 1 are moved to records on the first save of the account.
+    pub(crate) fn save_contacts(&mut self, account_id: &AccountId, contacts: Vec<ContactRecord>) {
+        if let Some(legacy_contacts) = self.legacy_accounts.remove(account_id) {
+            // contacts of version 1 were indexed by saved values and without skeletons
+            for contact in legacy_contacts {
+                let contact_stringified = contact.get_stringified();
+                if self.accounts_for_contacts.get(&contact_stringified).as_ref() == Some(account_id) {
+                    self.accounts_for_contacts.remove(&contact_stringified);
+                }
+            }
+            for record in contacts.iter().filter(|record| record.verification_method == VerificationMethod::Migration) {
+                self.insert_accounts_for_contact(account_id.clone(), record.contact.clone());
+            }
+        }
+
+        let records: Vec<VersionedContactRecord> = contacts.into_iter().map(VersionedContactRecord::from).collect();
+        self.accounts.insert(account_id, &records);
+    }
+
+    /// Contact of version 1 is verified by master account, time of its verification is unknown.
+    /// Saved value is normalized unless the normalized contact is already bound by another account,
+    /// the saved value is kept as display value.
+    fn get_legacy_record(&self, account_id: &AccountId, contact: ContactV1) -> ContactRecord {
+        let contact = Contact::from(contact);
+        let normalized_contact = self.normalize_contact(contact.clone());
+        let normalized_contact_owner = self.get_account_for_contact_stringified(self.get_contact_stringified(normalized_contact.clone()));
+        let contact = if normalized_contact.value == contact.value || normalized_contact_owner.is_some_and(|owner| &owner != account_id) {
+            contact
+        } else {
+            Contact {
+                display_value: Some(contact.value),
+                ..normalized_contact
+            }
+        };
+
+        ContactRecord {
+            contact,
+            verified_at: 0,
+            verified_by: self.master_account_id.clone(),
+            verification_method: VerificationMethod::Migration,
+            is_primary: false,
+            is_private: false,
+        }
+    }
+
+    pub fn get_account_for_contact(&self, contact: Contact) -> Option<AccountId> {
+        let normalized_contact = self.normalize_contact(contact.clone());
+        self.get_account_for_contact_stringified(self.get_contact_stringified(normalized_contact.clone()))
+            .or_else(|| self.get_account_for_contact_stringified(self.get_contact_stringified(self.get_private_contact(&normalized_contact))))
+            .or_else(|| if self.legacy_accounts.is_empty() {
+                None
+            } else {
+                // contacts of version 1 are indexed by saved values until they are moved
+                self.get_account_for_contact_stringified(format!("{}:{}", contact.category, contact.value))
+            })
+    }
+
+    pub fn get_account_for_contact_stringified(&self, contact_stringified: ContactStringified) -> Option<AccountId> {
+        self.accounts_for_contacts.get(&contact_stringified)
+    }
+
+    pub fn get_contacts_by_type(&self, account_id: AccountId, category: CategoryId) -> Option<Vec<String>> {
+        match self.get_contacts(account_id) {
+            Some(contacts) =>
+                {
+                    let filtered_contacts: Vec<String> = contacts
+                        .into_iter()
+                        .filter(|record| !record.is_private)
+                        .map(|record| record.contact)
+                        .filter(|contact| contact.category == category)
+                        .map(|contact| contact.display_value.unwrap_or(contact.value))
+                        .collect();
+                    Some(filtered_contacts)
+                }
+            None => None
+        }
+    }
+
+    /// Owner marks the bound contact as primary in its category. Marked contact is moved to the beginning of the list,
+    /// so the last marked contact is the preferred one across categories.
+    pub fn set_primary_contact(&mut self, contact: Contact) {
+        let account_id = env::predecessor_account_id();
+        let contact = self.normalize_contact(contact);
+
+        let contacts = self.get_contacts(account_id.clone()).unwrap_or_default();
+        let (mut primary_records, other_records): (Vec<ContactRecord>, Vec<ContactRecord>) = contacts
+            .into_iter()
+            .partition(|record| self.is_record_of_contact(record, &contact));
+        require(!primary_records.is_empty(), ContractError::NotContactOwner);
+
+        for record in primary_records.iter_mut() {
+            record.is_primary = true;
+        }
+        let primary_contact = primary_records[0].contact.clone();
+        let records: Vec<ContactRecord> = primary_records.into_iter()
+            .chain(other_records.into_iter().map(|record| ContactRecord {
+                is_primary: record.is_primary && record.contact.category != contact.category,
+                ..record
+            }))
+            .collect();
+
+        let initial_storage_usage = env::storage_usage();
+        self.save_contacts(&account_id, records);
+        self.update_storage_deposit(&account_id, initial_storage_usage);
+
+        Event::ContactPrimarySet(vec![ContactPrimaryData {
+            account_id,
+            contact: primary_contact,
+        }]).emit();
+    }
+
+    /// Primary contact of the category, or the first bound one if none is marked. Stale and private contacts are skipped.
+    pub fn get_primary_contact(&self, account_id: AccountId, category: CategoryId) -> Option<ContactRecord> {
+        let records: Vec<ContactRecord> = self.get_contacts(account_id)?
+            .into_iter()
+            .filter(|record| record.contact.category == category && !record.is_private && !self.is_contact_stale(record))
+            .collect();
+        let primary_index = records.iter().position(|record| record.is_primary).unwrap_or(0);
+
+        records.into_iter().nth(primary_index)
+    }
+
+    /// Preferred handle across categories: the last marked primary contact, or the first bound one.
+    /// Stale and private contacts are skipped.
+    pub fn resolve_display_identity(&self, account_id: AccountId) -> Option<DisplayIdentity> {
+        let records: Vec<ContactRecord> = self.get_contacts(account_id)?
+            .into_iter()
+            .filter(|record| !record.is_private && !self.is_contact_stale(record))
+            .collect();
+        let primary_index = records.iter().position(|record| record.is_primary).unwrap_or(0);
+
+        records.into_iter().nth(primary_index).map(|record| DisplayIdentity {
+            category: record.contact.category,
+            handle: record.contact.display_value.unwrap_or(record.contact.value),
+        })
+    }
+
+    pub fn has_request_key(&self, account_id: AccountId, category: CategoryId) -> bool {
+        self.get_request_key(account_id, category).is_some()
+    }
+
+
+    #[payable]
+    pub fn send(&mut self, contact: Contact) -> Promise {
+        let tokens: Balance = near_sdk::env::attached_deposit();
+
+        let contact = self.normalize_contact(contact);
+        let recipient = self.get_account_for_contact(contact.clone());
+        require(recipient.is_some(), ContractError::ContactNotFound);
+
+        let recipient_account_id = recipient.unwrap();
+        let is_stale = self.get_contact_record(recipient_account_id.clone(), &contact)
+            .is_some_and(|record| self.is_contact_stale(&record));
+        require(!is_stale, ContractError::ContactStale);
+
+        Event::TokensSent(vec![TokensSentData {
+            sender_id: env::predecessor_account_id(),
+            receiver_id: recipient_account_id.clone(),
+            amount: U128(tokens),
+        }]).emit();
+
+        Promise::new(recipient_account_id).transfer(tokens)
+    }
+
+    /// Hidden and private contacts are skipped.
+    pub fn get_all_accounts_for_contacts(&self, from_index: u64, limit: u64) -> Page<HashMap<ContactStringified, AccountId>> {
+        let keys = self.accounts_for_contacts.keys_as_vector();
+        let values = self.accounts_for_contacts.values_as_vector();
+
+        get_page(from_index, limit, keys.len(), |index| {
+            let contact_stringified = keys.get(index).unwrap();
+            let account_id = values.get(index).unwrap();
+            self.get_discoverable_contacts(&account_id).iter()
+                .any(|record| self.get_contact_stringified(record.contact.clone()) == contact_stringified)
+                .then_some((contact_stringified, account_id))
+        })
+    }
+
+    /// Accounts without discoverable contacts are skipped.
+    pub fn get_all_contacts(&self, from_index: u64, limit: u64) -> Page<HashMap<AccountId, Vec<ContactRecord>>> {
+        let keys = self.accounts.keys_as_vector();
+
+        get_page(from_index, limit, keys.len(), |index| {
+            let account_id = keys.get(index).unwrap();
+            let discoverable_contacts = self.get_discoverable_contacts(&account_id);
+            (!discoverable_contacts.is_empty()).then_some((account_id, discoverable_contacts))
+        })
+    }
+
+    /// Accounts without discoverable contacts of the category are skipped.
+    pub fn get_all_contacts_by_type(&self, category: CategoryId, from_index: u64, limit: u64) -> Page<HashMap<AccountId, Vec<String>>> {
+        let keys = self.accounts.keys_as_vector();
+
+        get_page(from_index, limit, keys.len(), |index| {
+            let account_id = keys.get(index).unwrap();
+            let contacts: Vec<String> = self.get_discoverable_contacts(&account_id)
+                .into_iter()
+                .map(|record| record.contact)
+                .filter(|contact| contact.category == category)
+                .map(|contact| contact.display_value.unwrap_or(contact.value))
+                .collect();
+            (!contacts.is_empty()).then_some((account_id, contacts))
+        })
+    }
+
+    /// Contacts of the account shown in enumeration views: not private and not hidden by discovery settings.
+    fn get_discoverable_contacts(&self, account_id: &AccountId) -> Vec<ContactRecord> {
+        let settings = self.get_discovery_settings(account_id.clone());
+        if settings.is_hidden {
+            return vec![];
+        }
+
+        self.get_contacts(account_id.clone()).unwrap_or_default()
+            .into_iter()
+            .filter(|record| !record.is_private && !settings.hidden_categories.contains(&record.contact.category))
+            .filter(|record| !settings.hidden_contacts.iter().any(|contact| self.are_contacts_equal(record.contact.clone(), contact.clone())))
+            .collect()
+    }
+
+    /// Owner chooses which contacts are hidden from enumeration views, hidden contacts have to be bound to the account.
+    /// Settings replace previous ones, storage difference is taken from or returned to the storage deposit.
+    pub fn set_discovery_settings(&mut self, is_hidden: bool, hidden_categories: Vec<CategoryId>, hidden_contacts: Vec<Contact>) {
+        let account_id = env::predecessor_account_id();
+
+        let hidden_contacts: Vec<Contact> = hidden_contacts.into_iter()
+            .map(|contact| {
+                // saved contact is used, so private contacts are not revealed
+                self.get_contact_record(account_id.clone(), &self.normalize_contact(contact))
+                    .unwrap_or_else(|| ContractError::NotContactOwner.panic())
+                    .contact
+            })
+            .collect();
+        let settings = DiscoverySettings {
+            is_hidden,
+            hidden_categories,
+            hidden_contacts,
+        };
+
+        let initial_storage_usage = env::storage_usage();
+        if settings == DiscoverySettings::default() {
+            self.discovery_settings.remove(&account_id);
+        } else {
+            self.discovery_settings.insert(&account_id, &settings);
+        }
+        self.update_storage_deposit(&account_id, initial_storage_usage);
+
+        Event::DiscoverySettingsSet(vec![DiscoverySettingsData {
+            account_id,
+            settings,
+        }]).emit();
+    }
+
+    pub fn get_discovery_settings(&self, account_id: AccountId) -> DiscoverySettings {
+        self.discovery_settings.get(&account_id).unwrap_or_default()
+    }
+
+    pub fn get_owners(&self, _contact: Contact) -> Vec<String> {
+        ContractError::Deprecated.panic()
+    }
+
+    /// Stale contacts are bound to the account but not reported as owned until they are verified again.
+    pub fn is_owner(&self, account_id: AccountId, contact: Contact) -> bool {
+        let contact = self.normalize_contact(contact);
+        match self.get_contact_record(account_id, &contact) {
+            Some(record) => !self.is_contact_stale(&record),
+            None => false
+        }
+    }
+
+    // remove contact
+    pub fn remove(&mut self, contact: Contact) -> bool {
+        let account_id = env::predecessor_account_id();
+        let contact = self.normalize_contact(contact);
+        // stale contacts can be removed as well
+        let is_owner = self.get_contact_record(account_id.clone(), &contact).is_some();
+
+        require(is_owner, ContractError::NotContactOwner);
+
+        match self.get_contacts(account_id.clone()) {
+            Some(contacts) =>
+                {
+                    let initial_storage_usage = env::storage_usage();
+
+                    let (removed_records, filtered_contacts): (Vec<ContactRecord>, Vec<ContactRecord>) = contacts
+                        .into_iter()
+                        .partition(|record| self.is_record_of_contact(record, &contact));
+                    self.save_contacts(&account_id, filtered_contacts);
+                    let removed_contacts: Vec<Contact> = removed_records.into_iter().map(|record| record.contact).collect();
+
+                    for removed_contact in removed_contacts.iter() {
+                        self.remove_accounts_for_contact(removed_contact.clone());
+                    }
+
+                    // contacts of version 1 moved by the save may take more storage than removed
+                    let tokens_per_entry_in_bytes = initial_storage_usage.saturating_sub(env::storage_usage());
+                    let tokens_per_entry_storage_price: Balance = Balance::from(tokens_per_entry_in_bytes) * STORAGE_PRICE_PER_BYTE;
                     let storage_paid = Contract::storage_paid(self, ValidAccountId::try_from(account_id.clone()).unwrap());
                     let balance: Balance = storage_paid.0 + tokens_per_entry_storage_price;
                     self.storage_deposits.insert(&account_id.clone(), &balance);
-                    env::log(format!("@{} unlocked {} yNEAR from storage", account_id, tokens_per_entry_storage_price).as_bytes());
+                    Event::ContactUnbound(vec![ContactData {
+                        account_id,
+                        contacts: removed_contacts,
+                        storage: U128(tokens_per_entry_storage_price),
+                    }]).emit();
 
                     true
                 }
@@ -441,23 +1773,28 @@ impl Contract {
     pub fn remove_all(&mut self) -> bool {
         let account_id = env::predecessor_account_id();
 
-        match self.accounts.get(&account_id) {
+        match self.get_contacts(account_id.clone()) {
             Some(contacts) =>
                 {
                     let initial_storage_usage = env::storage_usage();
+                    let contacts: Vec<Contact> = contacts.into_iter().map(|record| record.contact).collect();
 
                     for _contact in contacts.iter() {
                         self.remove_accounts_for_contact(_contact.clone());
                     }
 
-                    self.accounts.insert(&account_id, &vec![]);
+                    self.save_contacts(&account_id, vec![]);
 
                     let tokens_per_entry_in_bytes = initial_storage_usage - env::storage_usage();
                     let tokens_per_entry_storage_price: Balance = Balance::from(tokens_per_entry_in_bytes) * STORAGE_PRICE_PER_BYTE;
                     let storage_paid = Contract::storage_paid(self, ValidAccountId::try_from(account_id.clone()).unwrap());
                     let balance: Balance = storage_paid.0 + tokens_per_entry_storage_price;
                     self.storage_deposits.insert(&account_id, &balance);
-                    env::log(format!("@{} unlocked {} yNEAR from storage", account_id, tokens_per_entry_storage_price).as_bytes());
+                    Event::ContactUnbound(vec![ContactData {
+                        account_id,
+                        contacts,
+                        storage: U128(tokens_per_entry_storage_price),
+                    }]).emit();
 
                     true
                 }
@@ -471,15 +1808,17 @@ impl Contract {
             .map(|a| a.into())
             .unwrap_or_else(env::predecessor_account_id);
         let deposit = env::attached_deposit();
-        assert!(
-            deposit > 0,
-            "Requires positive deposit"
-        );
+        require(deposit > 0, ContractError::PositiveDepositRequired);
 
         // update storage
         let mut balance: u128 = self.storage_deposits.get(&storage_account_id).unwrap_or(0);
         balance += deposit;
         self.storage_deposits.insert(&storage_account_id, &balance);
+
+        Event::StorageDeposited(vec![StorageData {
+            account_id: storage_account_id,
+            amount: U128(deposit),
+        }]).emit();
     }
 
     #[payable]
@@ -487,6 +1826,11 @@ impl Contract {
         let owner_id = env::predecessor_account_id();
         let amount = self.storage_deposits.remove(&owner_id).unwrap_or(0);
         if amount > 0 {
+            Event::StorageWithdrawn(vec![StorageData {
+                account_id: owner_id.clone(),
+                amount: U128(amount),
+            }]).emit();
+
             Promise::new(owner_id).transfer(amount);
         }
     }
@@ -499,61 +1843,111 @@ impl Contract {
         U128(self.storage_deposits.get(account_id.as_ref()).unwrap_or(0))
     }
 
+    /// Migrates from the layout of version 1 with the enum of categories. Collections of version 1 are kept
+    /// and converted on read, `migrate_legacy_entries` moves them in batches.
     #[init(ignore_state)]
-    pub fn migrate_state_1() -> Self {
-        let migration_version: u16 = 1;
-        assert_eq!(env::predecessor_account_id(), env::current_account_id(), "Private function");
+    pub fn migrate_state_25() -> Self {
+        let migration_version: u16 = 25;
+        require(env::predecessor_account_id() == env::current_account_id(), ContractError::PrivateFunction);
 
         #[derive(BorshDeserialize)]
         struct OldContract {
             master_account_id: AccountId,
-            accounts: UnorderedMap<AccountId, Vec<Contact_v1>>,
-            requests: UnorderedMap<RequestKey, Request>,
+            accounts: UnorderedMap<AccountId, Vec<ContactV1>>,
+            accounts_for_contacts: UnorderedMap<ContactStringified, AccountId>,
+            requests: UnorderedMap<RequestKey, RequestV1>,
             storage_deposits: LookupMap<AccountId, Balance>,
+            #[allow(dead_code)]
+            version: u16,
         }
 
-        let old_contract: OldContract = env::state_read().expect("Old state doesn't exist");
-
-        let mut new_accounts = UnorderedMap::new(StorageKey::Accounts2.try_to_vec().unwrap());
+        let old_contract: OldContract = env::state_read().unwrap_or_else(|| ContractError::OldStateNotFound.panic());
 
-        let new_requests = UnorderedMap::new(StorageKey::Requests2.try_to_vec().unwrap());
-        let mut new_accounts_for_contacts = UnorderedMap::new(StorageKey::AccountsForContacts.try_to_vec().unwrap());
+        let mut categories = UnorderedMap::new(StorageKey::Categories.try_to_vec().unwrap());
+        for (category_id, category) in Category::defaults() {
+            categories.insert(&category_id, &category);
+        }
 
-        let data1_account = "example.near".to_string();
-        let data1_contact = get_telegram_contact("handler".to_string(), Some(123));
-        new_accounts.insert(&data1_account.clone(), &vec![data1_contact.clone()]);
-        new_accounts_for_contacts.insert(&Contract::get_contact_stringified(data1_contact), &data1_account);
+        let mut verifiers = UnorderedMap::new(StorageKey::Verifiers.try_to_vec().unwrap());
+        verifiers.insert(&old_contract.master_account_id, &categories.keys().collect());
 
+        // stale collections of version 0 were never cleared, their prefixes aren't reused
         Self {
             master_account_id: old_contract.master_account_id,
-            accounts: new_accounts,
-            accounts_for_contacts: new_accounts_for_contacts,
-            requests: new_requests,
+            accounts: UnorderedMap::new(StorageKey::Accounts25.try_to_vec().unwrap()),
+            accounts_for_contacts: old_contract.accounts_for_contacts,
+            requests: UnorderedMap::new(StorageKey::Requests25.try_to_vec().unwrap()),
             storage_deposits: old_contract.storage_deposits,
             version: migration_version,
+            request_keys: LookupMap::new(StorageKey::RequestKeys.try_to_vec().unwrap()),
+            verifier_keys: UnorderedMap::new(StorageKey::VerifierKeys.try_to_vec().unwrap()),
+            used_nonces: LookupSet::new(StorageKey::UsedNonces.try_to_vec().unwrap()),
+            verifiers,
+            failed_confirmations: LookupMap::new(StorageKey::FailedConfirmations.try_to_vec().unwrap()),
+            categories,
+            email_domain_rules: Contract::get_default_email_domain_rules(),
+            contact_skeletons: LookupMap::new(StorageKey::ContactSkeletons.try_to_vec().unwrap()),
+            contact_salt: near_sdk::bs58::encode(env::random_seed()).into_string(),
+            discovery_settings: LookupMap::new(StorageKey::DiscoverySettings.try_to_vec().unwrap()),
+            legacy_accounts: old_contract.accounts,
+            legacy_requests: old_contract.requests,
+        }
+    }
+
+    /// Master account moves up to `limit` accounts and requests of version 1, returns the number of entries left.
+    /// Contacts are saved as normalized records with skeletons, new contacts can't be bound until all of them are moved. Requests have keys which aren't namespaced by account
+    /// and can't be confirmed anymore, so they are removed and the whitelist storage cost is refunded.
+    pub fn migrate_legacy_entries(&mut self, limit: u64) -> U64 {
+        require(env::predecessor_account_id() == self.master_account_id, ContractError::NoAccess);
+        require(limit <= 100, ContractError::LimitTooHigh);
+
+        let account_ids: Vec<AccountId> = self.legacy_accounts.keys().take(limit as usize).collect();
+        for account_id in account_ids.iter() {
+            let records = self.get_contacts(account_id.clone()).unwrap_or_default();
+            self.save_contacts(account_id, records);
+        }
+
+        let request_keys: Vec<RequestKey> = self.legacy_requests.keys().take(limit as usize - account_ids.len()).collect();
+        for request_key in request_keys {
+            let request = self.legacy_requests.remove(&request_key).unwrap();
+            self.refund_request(&request.account_id, true);
         }
+
+        U64(self.legacy_accounts.len() + self.legacy_requests.len())
     }
 
     pub fn get_version(&self) -> u16 {
         self.version
     }
+
+    pub fn get_error_codes(&self) -> Vec<ErrorDescription> {
+        ContractError::all().into_iter().map(ErrorDescription::from).collect()
+    }
 }
 
 /* UTILS */
 pub(crate) fn assert_one_yocto() {
-    assert_eq!(
-        env::attached_deposit(),
-        1,
-        "Requires attached deposit of exactly 1 yoctoNEAR",
-    )
+    require(env::attached_deposit() == 1, ContractError::OneYoctoRequired)
 }
 
-// for migration
-pub(crate) fn get_telegram_contact(value: String, account_id: Option<u64>) -> Contact {
-    Contact {
-        category: ContactCategories::Telegram,
-        value,
-        account_id,
+/// Collects up to `limit` items of the map entries starting at `from_index`, entries without an item are skipped.
+/// At most `MAX_SCANNED_ENTRIES` entries are scanned, so a page of a sparse map may be shorter than `limit`.
+fn get_page<T, C: std::iter::FromIterator<T>>(from_index: u64, limit: u64, len: u64, get_item: impl Fn(u64) -> Option<T>) -> Page<C> {
+    require(limit <= 100, ContractError::LimitTooHigh);
+
+    let scan_end_index = std::cmp::min(from_index.saturating_add(MAX_SCANNED_ENTRIES), len);
+    let mut items = vec![];
+    let mut index = from_index;
+    while index < scan_end_index && (items.len() as u64) < limit {
+        if let Some(item) = get_item(index) {
+            items.push(item);
+        }
+        index += 1;
+    }
+
+    Page {
+        items: items.into_iter().collect(),
+        next_index: if index < len { Some(U64(index)) } else { None },
     }
 }
 
@@ -576,47 +1970,176 @@ mod tests {
 
     fn bob_valid_account() -> ValidAccountId { ValidAccountId::try_from(bob_account()).unwrap() }
 
-    fn alice_request_key() -> RequestKey { digest(alice_secret_key()).to_string() }
+    fn telegram_bot_account() -> AccountId { "telegram_bot.near".to_string() }
+
+    fn telegram_bot_valid_account() -> ValidAccountId { ValidAccountId::try_from(telegram_bot_account()).unwrap() }
+
+    fn alice_request_key() -> RequestKey { digest(format!("{}:{}", alice_account(), alice_secret_key())) }
 
     fn alice_secret_key() -> SecretKey { "be1AcEnEsBVV4UuoZ6qGGHRFK3HDwckDj7pctw83BbkR7JJsQLs7y1gbv78f1o7UkqFAHX45CA82UPT7kDdBaSL".to_string() }
 
-    fn bob_request_key() -> RequestKey { digest(bob_secret_key()).to_string() }
+    fn alice_email_request_key() -> RequestKey { digest(format!("{}:{}", alice_account(), alice_email_secret_key())) }
+
+    fn alice_email_secret_key() -> SecretKey { "EMAIL_KEY_be1AcEnEsBVV4UuoZ6qGGHRFK3HDwckDj7pctw83BbkR7JJsQLs7y1gbv78f1o7UkqFAHX45CA82U".to_string() }
+
+    fn bob_request_key() -> RequestKey { digest(format!("{}:{}", bob_account(), bob_secret_key())) }
 
     fn bob_secret_key() -> SecretKey { "WRONG_KEY_be1AcEnEsBVV4UuoZ6qGGHRFK3HDwckDj7pctw83BbkR7JJsQLs7y1gbv78f1o7UkqFAHX45CA82U".to_string() }
 
     fn alice_contact() -> Contact {
         Contact {
-            category: ContactCategories::Telegram,
+            category: CATEGORY_TELEGRAM.to_string(),
             value: "account_123".to_string(),
-            account_id: 1,
+            account_id: Some(U64(1)),
+            display_value: None,
+        }
+    }
+
+    fn alice_otp_code() -> SecretKey { "482913".to_string() }
+
+    fn alice_otp_request_key() -> RequestKey { digest("OTP_REQUEST_be1AcEnEsBVV4UuoZ6qGGHRFK3HDwckDj7pctw83BbkR7JJsQLs7y1g".to_string()) }
+
+    fn alice_email_contact() -> Contact {
+        Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: "alice@example.com".to_string(),
+            account_id: None,
+            display_value: None,
         }
     }
 
     fn bob_contact() -> Contact {
         Contact {
-            category: ContactCategories::Telegram,
+            category: CATEGORY_TELEGRAM.to_string(),
             value: "account_456".to_string(),
-            account_id: 2,
+            account_id: Some(U64(2)),
+            display_value: None,
         }
     }
 
 
-    pub fn get_context(
-        predecessor_account_id: AccountId,
-        attached_deposit: u128,
-        is_view: bool,
-    ) -> VMContext {
-        VMContext {
-            current_account_id: predecessor_account_id.clone(),
-            signer_account_id: predecessor_account_id.clone(),
-            signer_account_pk: vec![0, 1, 2],
-            predecessor_account_id,
-            input: vec![],
-            block_index: 1,
-            block_timestamp: 0,
-            epoch_height: 1,
-            account_balance: 0,
-            account_locked_balance: 0,
+    fn verifier_keypair() -> ed25519_dalek::Keypair {
+        let secret = ed25519_dalek::SecretKey::from_bytes(&[7u8; 32]).unwrap();
+        let public = ed25519_dalek::PublicKey::from(&secret);
+        ed25519_dalek::Keypair { secret, public }
+    }
+
+    fn verifier_public_key() -> Base58PublicKey {
+        let mut public_key = vec![CurveType::ED25519 as u8];
+        public_key.extend_from_slice(verifier_keypair().public.as_bytes());
+        Base58PublicKey(public_key)
+    }
+
+    fn sign_claim(account_id: AccountId, contact: ContactStringified, value: &str, expires_at: u64, nonce: u64) -> Base64VecU8 {
+        use ed25519_dalek::Signer;
+        let message = ClaimMessage {
+            account_id,
+            contact,
+            value: value.to_string(),
+            contract_id: alice_account(),
+            expires_at,
+            nonce,
+        }.try_to_vec().unwrap();
+        Base64VecU8(verifier_keypair().sign(&message).to_bytes().to_vec())
+    }
+
+    fn get_contract_with_verifier_key() -> Contract {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.add_verifier_key(verifier_public_key(), vec![CATEGORY_TELEGRAM.to_string(), CATEGORY_EMAIL.to_string(), CATEGORY_PHONE.to_string()]);
+
+        contract
+    }
+
+    /// Writes state of version 1 with contacts indexed as they were saved.
+    fn write_version_1_state(accounts: Vec<(AccountId, Vec<ContactV1>)>, requests: Vec<(RequestKey, RequestV1)>) {
+        #[derive(BorshSerialize)]
+        struct OldContract {
+            master_account_id: AccountId,
+            accounts: UnorderedMap<AccountId, Vec<ContactV1>>,
+            accounts_for_contacts: UnorderedMap<ContactStringified, AccountId>,
+            requests: UnorderedMap<RequestKey, RequestV1>,
+            storage_deposits: LookupMap<AccountId, Balance>,
+            version: u16,
+        }
+
+        let mut old_contract = OldContract {
+            master_account_id: master_account(),
+            accounts: UnorderedMap::new(StorageKey::Accounts2.try_to_vec().unwrap()),
+            accounts_for_contacts: UnorderedMap::new(StorageKey::AccountsForContacts.try_to_vec().unwrap()),
+            requests: UnorderedMap::new(StorageKey::Requests2.try_to_vec().unwrap()),
+            storage_deposits: LookupMap::new(StorageKey::StorageDeposits.try_to_vec().unwrap()),
+            version: 1,
+        };
+        for (account_id, contacts) in accounts {
+            for contact in contacts.iter() {
+                old_contract.accounts_for_contacts.insert(&contact.get_stringified(), &account_id);
+            }
+            old_contract.accounts.insert(&account_id, &contacts);
+        }
+        for (request_key, request) in requests {
+            old_contract.storage_deposits.insert(&request.account_id, &0);
+            old_contract.requests.insert(&request_key, &request);
+        }
+
+        env::state_write(&old_contract);
+    }
+
+    /// Alice's Telegram contact is verified by telegram bot at timestamp 0,
+    /// verification of Telegram contacts expires after 100 nanoseconds.
+    fn get_contract_with_expiring_contact() -> Contract {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.add_verifier(telegram_bot_valid_account(), vec![CATEGORY_TELEGRAM.to_string()]);
+        contract.set_max_verification_age(CATEGORY_TELEGRAM.to_string(), Some(U64(100)));
+
+// switch to a context with verifier
+        let context = get_context(telegram_bot_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(alice_secret_key());
+
+        contract
+    }
+
+    pub fn get_context(
+        predecessor_account_id: AccountId,
+        attached_deposit: u128,
+        is_view: bool,
+    ) -> VMContext {
+        VMContext {
+            current_account_id: predecessor_account_id.clone(),
+            signer_account_id: predecessor_account_id.clone(),
+            signer_account_pk: vec![0, 1, 2],
+            predecessor_account_id,
+            input: vec![],
+            block_index: 1,
+            block_timestamp: 0,
+            epoch_height: 1,
+            account_balance: 0,
+            account_locked_balance: 0,
             storage_usage: 10u64.pow(6),
             attached_deposit,
             prepaid_gas: 10u64.pow(15),
@@ -639,296 +2162,2505 @@ mod tests {
 
         contract.storage_deposit(Some(alice_valid_account()));
 
-        assert_eq!(
-            ntoy(100),
-            contract.storage_paid(alice_valid_account()).0
-        );
+        assert_eq!(
+            ntoy(100),
+            contract.storage_paid(alice_valid_account()).0
+        );
+    }
+
+    #[test]
+    fn test_storage_deposit_and_withdraw() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+        contract.storage_withdraw();
+
+        assert_eq!(
+            0,
+            contract.storage_paid(alice_valid_account()).0
+        );
+    }
+
+    #[test]
+    fn whitelist_key() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before == storage_paid_after + WHITELIST_STORAGE_COST,
+                "Wrong storage deposit for whitelist {} / {}", storage_paid_before, storage_paid_after);
+
+        let alice_key = contract.get_request_key(alice_account(), CATEGORY_TELEGRAM.to_string());
+        assert_eq!(alice_key, Some(alice_request_key()), "Key wasn't added");
+
+        let bob_key = contract.get_request_key(bob_account(), CATEGORY_TELEGRAM.to_string());
+        assert!(bob_key != Some(bob_request_key()), "Wrong key added");
+
+        let alice_has_key = contract.has_request_key(alice_account(), CATEGORY_TELEGRAM.to_string());
+        assert!(alice_has_key, "Key wasn't added");
+
+        let bob_has_key = contract.has_request_key(bob_account(), CATEGORY_TELEGRAM.to_string());
+        assert!(!bob_has_key, "Wrong key added");
+
+        let request: Request = contract.get_request(alice_request_key()).unwrap();
+        assert_eq!(request.account_id, alice_account(), "Key wasn't added");
+        assert!(request.account_id != bob_account(), "Wrong key added");
+        assert!(request.contact.is_none(), "Contact not empty");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn whitelist_by_user() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+        contract.whitelist_key(alice_valid_account(), bob_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_ALREADY_EXISTS:")]
+    fn whitelist_twice() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        contract.whitelist_key(alice_valid_account(), bob_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NOT_ENOUGH_STORAGE_DEPOSIT:")]
+    fn whitelist_without_storage() {
+        let context = get_context(alice_account(), ntoy(1) / 1000, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_KEY_ALREADY_USED:")]
+    fn whitelist_same_key_for_different_accounts() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+        contract.storage_deposit(Some(bob_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        contract.whitelist_key(bob_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+    }
+
+    #[test]
+    fn remove_request_after_whitelist() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        contract.remove_request(CATEGORY_TELEGRAM.to_string());
+
+        let request: Option<Request> = contract.get_request(alice_request_key());
+        assert!(request.is_none(), "Request was not removed");
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before == storage_paid_after + WHITELIST_FEE,
+                "Wrong storage deposit for remove_request {} / {}", storage_paid_before, storage_paid_after);
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        let alice_has_key = contract.has_request_key(alice_account(), CATEGORY_TELEGRAM.to_string());
+        assert!(alice_has_key, "Key wasn't added on a second time");
+    }
+
+    #[test]
+    fn start_auth() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+
+        let request: Request = contract.get_request(alice_request_key()).unwrap();
+        assert!(request.contact == Some(alice_contact()), "Contact wasn't properly saved");
+    }
+
+    #[test]
+    fn remove_request_after_start_auth() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+
+        contract.remove_request(CATEGORY_TELEGRAM.to_string());
+
+        let request: Option<Request> = contract.get_request(alice_request_key());
+        assert!(request.is_none(), "Request was not removed");
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before == storage_paid_after + WHITELIST_FEE,
+                "Wrong storage deposit for remove_request {} / {}", storage_paid_before, storage_paid_after);
+    }
+
+    #[test]
+    fn confirm_auth() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+
+        let secret_key: SecretKey = digest(alice_secret_key());
+        assert!(secret_key == "9f763044a36137644ca87a50545c3eff219345d8490d1c1db597105411315a9a", "Wrong secret key generation");
+        contract.confirm_auth(alice_secret_key());
+
+        let alice_is_owner = contract.is_owner(alice_account(), alice_contact());
+        assert!(alice_is_owner, "Contact wasn't created");
+
+        let bob_is_owner = contract.is_owner(bob_account(), alice_contact());
+        assert!(!bob_is_owner, "Wrong contact owner");
+
+        assert!(!contract.has_request_key(alice_account(), CATEGORY_TELEGRAM.to_string()), "Request key wasn't removed from index");
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before > storage_paid_after,
+                "Storage deposit wasn't reduced after adding an item {} / {}", storage_paid_before, storage_paid_after);
+    }
+
+    #[test]
+    fn confirm_auth_with_wrong_key() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+
+        assert!(!contract.confirm_auth(bob_secret_key()), "Wrong key confirmed");
+        assert!(!contract.is_owner(alice_account(), alice_contact()), "Contact was created");
+        assert_eq!(contract.get_remaining_attempts(alice_account()), MAX_FAILED_CONFIRMATIONS - 1, "Failed attempt wasn't saved");
+    }
+
+    #[test]
+    fn confirm_auth_without_request() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        let initial_storage_usage = env::storage_usage();
+        assert!(!contract.confirm_auth(bob_secret_key()), "Wrong key confirmed");
+        assert_eq!(env::storage_usage(), initial_storage_usage, "Failed attempt was saved");
+        assert_eq!(contract.get_remaining_attempts(alice_account()), MAX_FAILED_CONFIRMATIONS, "Failed attempt was counted");
+    }
+
+    #[test]
+    fn confirm_auth_with_wrong_user() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+
+// switch back to a context with user
+        let context = get_context(bob_account(), 1, false);
+        testing_env!(context.clone());
+
+        assert!(!contract.confirm_auth(alice_secret_key()), "Request of different account confirmed");
+        assert!(!contract.is_owner(bob_account(), alice_contact()), "Contact was created");
+        assert!(contract.get_request(alice_request_key()).is_some(), "Request was removed");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_ALREADY_REGISTERED:")]
+    fn add_same_contact_twice() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(alice_secret_key());
+
+// switch to bob
+
+        let context = get_context(bob_account(), ntoy(100), false);
+        testing_env!(context.clone());
+        contract.storage_deposit(Some(bob_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(bob_valid_account(), bob_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(bob_secret_key());
+    }
+
+    #[test]
+    fn remove_contact() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(alice_secret_key());
+
+        assert!(contract.is_owner(alice_account(), alice_contact()), "Contact wasn't created");
+
+        contract.remove(alice_contact());
+
+        assert!(!contract.is_owner(alice_account(), alice_contact()), "Contact wasn't removed");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_NOT_FOUND:")]
+    fn send_to_contact() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(alice_secret_key());
+
+// send from bob
+
+        let context = get_context(bob_account(), ntoy(75), false);
+        testing_env!(context.clone());
+        contract.send(alice_contact());
+
+        let context = get_context(bob_account(), ntoy(75), false);
+        testing_env!(context.clone());
+        contract.send(bob_contact());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_EXPIRED:")]
+    fn start_auth_after_expiration() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user after the TTL
+        let mut context = get_context(alice_account(), 1, false);
+        context.block_timestamp = REQUEST_TTL;
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_EXPIRED:")]
+    fn confirm_auth_after_expiration() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+
+        let mut context = get_context(alice_account(), 0, false);
+        context.block_timestamp = REQUEST_TTL;
+        testing_env!(context.clone());
+
+        contract.confirm_auth(alice_secret_key());
+    }
+
+    #[test]
+    fn sweep_expired_requests() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+        let context = get_context(bob_account(), ntoy(100), false);
+        testing_env!(context.clone());
+        contract.storage_deposit(Some(bob_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        let mut context = get_context(master_account(), 0, false);
+        context.block_timestamp = REQUEST_TTL / 2;
+        testing_env!(context.clone());
+        contract.whitelist_key(bob_valid_account(), bob_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// sweep by anyone when only alice's request has expired
+        let mut context = get_context(bob_account(), 0, false);
+        context.block_timestamp = REQUEST_TTL;
+        testing_env!(context.clone());
+
+        assert_eq!(contract.sweep_expired_requests(0, 10), 1, "Wrong number of swept requests");
+
+        assert!(contract.get_request(alice_request_key()).is_none(), "Expired request was not removed");
+        assert!(contract.get_request(bob_request_key()).is_some(), "Active request was removed");
+        assert!(!contract.has_request_key(alice_account(), CATEGORY_TELEGRAM.to_string()), "Request key wasn't removed from index");
+        assert_eq!(contract.get_request_key(bob_account(), CATEGORY_TELEGRAM.to_string()), Some(bob_request_key()), "Wrong key in index");
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before == storage_paid_after + WHITELIST_FEE,
+                "Wrong storage deposit after sweep {} / {}", storage_paid_before, storage_paid_after);
+    }
+
+    #[test]
+    fn confirm_auth_for_several_categories() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+        contract.whitelist_key(alice_valid_account(), alice_email_request_key(), CATEGORY_EMAIL.to_string(), None);
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before == storage_paid_after + 2 * WHITELIST_STORAGE_COST,
+                "Wrong storage deposit for two requests {} / {}", storage_paid_before, storage_paid_after);
+        assert_eq!(contract.get_request_keys(alice_account()).len(), 2, "Wrong number of pending requests");
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.start_auth(alice_email_request_key(), alice_email_contact(), None);
+
+        contract.confirm_auth(alice_email_secret_key());
+        assert!(contract.is_owner(alice_account(), alice_email_contact()), "Email wasn't created");
+        assert!(contract.has_request_key(alice_account(), CATEGORY_TELEGRAM.to_string()), "Telegram request was removed");
+        assert!(!contract.has_request_key(alice_account(), CATEGORY_EMAIL.to_string()), "Email request wasn't removed");
+
+        contract.confirm_auth(alice_secret_key());
+        assert!(contract.is_owner(alice_account(), alice_contact()), "Telegram wasn't created");
+        assert!(contract.get_request_keys(alice_account()).is_empty(), "Requests weren't removed");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_CATEGORY_MISMATCH:")]
+    fn start_auth_with_wrong_category() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_email_contact(), None);
+    }
+
+    #[test]
+    fn start_auth_with_whitelisted_contact() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        let contact_hash = contract.get_contact_hash(Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: " Alice@Example.com".to_string(),
+            account_id: None,
+            display_value: None,
+        });
+        contract.whitelist_key(alice_valid_account(), alice_email_request_key(), CATEGORY_EMAIL.to_string(), Some(contact_hash));
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_email_request_key(), alice_email_contact(), None);
+        contract.confirm_auth(alice_email_secret_key());
+
+        assert!(contract.is_owner(alice_account(), alice_email_contact()), "Contact wasn't created");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_NOT_WHITELISTED:")]
+    fn start_auth_with_not_whitelisted_contact() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        let contact_hash = contract.get_contact_hash(alice_contact());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), Some(contact_hash));
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), bob_contact(), None);
+    }
+
+    #[test]
+    fn claim_contact() {
+        let mut contract = get_contract_with_verifier_key();
+        assert_eq!(contract.get_verifier_keys().get(&String::from(&verifier_public_key())).map(|categories| categories.len()), Some(3), "Verifier key wasn't added");
+
+// current account is alice.near in test contexts
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+        let signature = sign_claim(alice_account(), "Telegram:1".to_string(), "account_123", 100, 1);
+        contract.claim_contact(alice_contact(), U64(100), U64(1), signature, None);
+
+        assert!(contract.is_owner(alice_account(), alice_contact()), "Contact wasn't created");
+        let record = &contract.get_contacts(alice_account()).unwrap()[0];
+        assert_eq!(record.verified_by, String::from(&verifier_public_key()), "Wrong verifier");
+        assert_eq!(record.verification_method, VerificationMethod::Attestation, "Wrong verification method");
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before > storage_paid_after + WHITELIST_FEE,
+                "Storage deposit wasn't reduced after claim {} / {}", storage_paid_before, storage_paid_after);
+    }
+
+    #[test]
+    fn claim_mixed_case_contact() {
+        let mut contract = get_contract_with_verifier_key();
+        let mixed_case_contact = Contact { value: " Alice@Example.com".to_string(), ..alice_email_contact() };
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        let signature = sign_claim(alice_account(), "Email:alice@example.com".to_string(), "alice@example.com", 100, 1);
+        contract.claim_contact(mixed_case_contact, U64(100), U64(1), signature, None);
+
+        let record = &contract.get_contacts(alice_account()).unwrap()[0];
+        assert_eq!(record.contact.value, "alice@example.com", "Contact wasn't normalized");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_SIGNATURE:")]
+    fn claim_contact_signed_for_different_account() {
+        let mut contract = get_contract_with_verifier_key();
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let signature = sign_claim(bob_account(), "Telegram:1".to_string(), "account_123", 100, 1);
+        contract.claim_contact(alice_contact(), U64(100), U64(1), signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_SIGNATURE:")]
+    fn claim_contact_with_removed_verifier_key() {
+        let mut contract = get_contract_with_verifier_key();
+        contract.remove_verifier_key(verifier_public_key());
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let signature = sign_claim(alice_account(), "Telegram:1".to_string(), "account_123", 100, 1);
+        contract.claim_contact(alice_contact(), U64(100), U64(1), signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_SIGNATURE:")]
+    fn claim_contact_of_category_not_allowed_for_verifier_key() {
+        let mut contract = get_contract_with_verifier_key();
+        contract.add_verifier_key(verifier_public_key(), vec![CATEGORY_EMAIL.to_string()]);
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let signature = sign_claim(alice_account(), "Telegram:1".to_string(), "account_123", 100, 1);
+        contract.claim_contact(alice_contact(), U64(100), U64(1), signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_INVALID_SIGNATURE:")]
+    fn claim_contact_with_value_not_signed() {
+        let mut contract = get_contract_with_verifier_key();
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let signature = sign_claim(alice_account(), "Telegram:1".to_string(), "account_123", 100, 1);
+        contract.claim_contact(Contact { value: "durov".to_string(), ..alice_contact() }, U64(100), U64(1), signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_SIGNATURE_EXPIRED:")]
+    fn claim_contact_after_expiration() {
+        let mut contract = get_contract_with_verifier_key();
+
+        let mut context = get_context(alice_account(), 0, false);
+        context.block_timestamp = 100;
+        testing_env!(context.clone());
+
+        let signature = sign_claim(alice_account(), "Telegram:1".to_string(), "account_123", 100, 1);
+        contract.claim_contact(alice_contact(), U64(100), U64(1), signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NONCE_ALREADY_USED:")]
+    fn claim_contact_with_used_nonce() {
+        let mut contract = get_contract_with_verifier_key();
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let signature = sign_claim(alice_account(), "Telegram:1".to_string(), "account_123", 100, 1);
+        contract.claim_contact(alice_contact(), U64(100), U64(1), signature, None);
+
+        contract.remove(alice_contact());
+
+        let signature = sign_claim(alice_account(), "Telegram:1".to_string(), "account_123", 100, 1);
+        contract.claim_contact(alice_contact(), U64(100), U64(1), signature, None);
+    }
+
+    #[test]
+    fn whitelist_by_category_verifier() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.add_verifier(telegram_bot_valid_account(), vec![CATEGORY_TELEGRAM.to_string()]);
+        contract.remove_verifier(master_valid_account());
+
+        let verifiers = contract.get_verifiers();
+        assert_eq!(verifiers.len(), 1, "Wrong number of verifiers");
+        assert_eq!(verifiers.get(&telegram_bot_account()), Some(&vec![CATEGORY_TELEGRAM.to_string()]), "Verifier wasn't added");
+
+// switch to a context with verifier
+        let context = get_context(telegram_bot_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        assert!(contract.has_request_key(alice_account(), CATEGORY_TELEGRAM.to_string()), "Key wasn't added");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn whitelist_by_verifier_of_other_category() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.add_verifier(telegram_bot_valid_account(), vec![CATEGORY_TELEGRAM.to_string()]);
+
+// switch to a context with verifier
+        let context = get_context(telegram_bot_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_email_request_key(), CATEGORY_EMAIL.to_string(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn add_verifier_by_user() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.add_verifier(alice_valid_account(), vec![CATEGORY_TELEGRAM.to_string()]);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_TOO_MANY_FAILED_ATTEMPTS:")]
+    fn confirm_auth_after_too_many_failed_attempts() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+
+        for _ in 0..MAX_FAILED_CONFIRMATIONS {
+            contract.confirm_auth(bob_secret_key());
+        }
+        assert_eq!(contract.get_remaining_attempts(alice_account()), 0, "Account wasn't locked");
+
+        contract.confirm_auth(alice_secret_key());
+    }
+
+    #[test]
+    fn confirm_auth_after_failed_attempts_window() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+
+        for _ in 0..MAX_FAILED_CONFIRMATIONS {
+            contract.confirm_auth(bob_secret_key());
+        }
+
+        let mut context = get_context(alice_account(), 0, false);
+        context.block_timestamp = FAILED_CONFIRMATIONS_WINDOW;
+        testing_env!(context.clone());
+
+        assert_eq!(contract.get_remaining_attempts(alice_account()), MAX_FAILED_CONFIRMATIONS, "Account wasn't unlocked");
+        assert!(contract.confirm_auth(alice_secret_key()), "Request wasn't confirmed");
+        assert!(contract.is_owner(alice_account(), alice_contact()), "Contact wasn't created");
+    }
+
+    fn get_contract_with_otp_request() -> Contract {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_otp(alice_valid_account(), alice_otp_request_key(), alice_email_contact());
+
+        contract
+    }
+
+    #[test]
+    fn confirm_otp() {
+        let mut contract = get_contract_with_otp_request();
+
+        let request = contract.get_request(alice_otp_request_key()).unwrap();
+        assert!(request.contact == Some(alice_email_contact()), "Contact wasn't saved");
+        assert_eq!(request.expires_at, OTP_TTL, "Wrong OTP expiration");
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+        contract.confirm_otp(alice_otp_request_key());
+        assert!(contract.is_owner(alice_account(), alice_email_contact()), "Contact wasn't created");
+        let record = &contract.get_contacts(alice_account()).unwrap()[0];
+        assert_eq!(record.verified_by, master_account(), "Wrong verifier");
+        assert_eq!(record.verification_method, VerificationMethod::Otp, "Wrong verification method");
+        assert!(contract.get_request(alice_otp_request_key()).is_none(), "Request wasn't removed");
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_after > storage_paid_before,
+                "Whitelist storage wasn't returned {} / {}", storage_paid_before, storage_paid_after);
+    }
+
+    #[test]
+    fn reject_otp() {
+        let mut contract = get_contract_with_otp_request();
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+        for attempt in 1..OTP_MAX_ATTEMPTS {
+            contract.reject_otp(alice_otp_request_key());
+            let request = contract.get_request(alice_otp_request_key()).unwrap();
+            assert_eq!(request.otp.unwrap().attempts, attempt, "Attempt wasn't saved");
+        }
+
+        contract.reject_otp(alice_otp_request_key());
+        assert!(contract.get_request(alice_otp_request_key()).is_none(), "Request wasn't removed after last attempt");
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before + WHITELIST_STORAGE_COST - WHITELIST_FEE == storage_paid_after,
+                "Wrong storage deposit after removal {} / {}", storage_paid_before, storage_paid_after);
+    }
+
+    #[test]
+    fn confirm_auth_with_otp_code() {
+        let mut contract = get_contract_with_otp_request();
+
+        // nothing in the state depends on the code, so it can't be guessed offline
+        let request = contract.get_request(alice_otp_request_key()).unwrap();
+        assert!(request.otp == Some(Otp { attempts: 0 }), "Code was saved");
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        assert!(!contract.confirm_auth(alice_otp_code()), "Request was confirmed by user");
+        assert!(!contract.is_owner(alice_account(), alice_email_contact()), "Contact was created");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn confirm_otp_by_user() {
+        let mut contract = get_contract_with_otp_request();
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        contract.confirm_otp(alice_otp_request_key());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_WITHOUT_OTP:")]
+    fn confirm_otp_of_key_request() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        contract.confirm_otp(alice_request_key());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_EXPIRED:")]
+    fn confirm_expired_otp() {
+        let mut contract = get_contract_with_otp_request();
+
+// switch to a context with master_account after OTP TTL
+        let mut context = get_context(master_account(), 0, false);
+        context.block_timestamp = OTP_TTL;
+        testing_env!(context.clone());
+
+        contract.confirm_otp(alice_otp_request_key());
+    }
+
+    #[test]
+    fn cancel_request_by_admin() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+        contract.whitelist_key(alice_valid_account(), alice_email_request_key(), CATEGORY_EMAIL.to_string(), None);
+
+        assert_eq!(contract.cancel_request(Some(alice_email_request_key()), None, true), 1, "Wrong number of cancelled requests");
+        assert!(contract.get_request(alice_email_request_key()).is_none(), "Request wasn't cancelled");
+        assert!(contract.get_request(alice_request_key()).is_some(), "Wrong request cancelled");
+
+        assert_eq!(contract.cancel_request(None, Some(alice_valid_account()), false), 1, "Wrong number of cancelled requests");
+        assert!(contract.get_request_keys(alice_account()).is_empty(), "Requests weren't cancelled");
+
+        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
+        assert!(storage_paid_before == storage_paid_after + WHITELIST_FEE,
+                "Wrong storage deposit after cancel {} / {}", storage_paid_before, storage_paid_after);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn cancel_request_by_user() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch to a context with user
+        let context = get_context(bob_account(), 0, false);
+        testing_env!(context.clone());
+        contract.cancel_request(Some(alice_request_key()), None, true);
+    }
+
+    #[test]
+    fn storage_deposit_event() {
+        let context = get_context(alice_account(), ntoy(1), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+        assert_eq!(
+            near_sdk::test_utils::get_logs(),
+            vec![r#"EVENT_JSON:{"standard":"nearauth","version":"1.0.0","event":"storage_deposited","data":[{"account_id":"alice.near","amount":"1000000000000000000000000"}]}"#]
+        );
+    }
+
+    #[test]
+    fn confirm_auth_events() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        let logs = near_sdk::test_utils::get_logs();
+        assert_eq!(logs.len(), 1, "Wrong number of events");
+        assert!(logs[0].starts_with(r#"EVENT_JSON:{"standard":"nearauth","version":"1.0.0","event":"request_created","data":[{"account_id":"alice.near","#), "Wrong event {}", logs[0]);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(alice_secret_key());
+
+        let logs = near_sdk::test_utils::get_logs();
+        assert_eq!(logs.len(), 3, "Wrong number of events");
+        assert!(logs[0].contains(r#""event":"request_started""#), "Wrong event {}", logs[0]);
+        assert!(logs[1].contains(r#""event":"request_confirmed""#), "Wrong event {}", logs[1]);
+        assert!(logs[2].contains(r#""event":"contact_bound","data":[{"account_id":"alice.near","contacts":[{"category":"Telegram","value":"account_123","account_id":"1"}]"#), "Wrong event {}", logs[2]);
+    }
+
+    #[test]
+    fn add_category() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        assert_eq!(contract.get_categories().len(), 7, "Wrong number of default categories");
+
+        contract.add_category("Mastodon".to_string(), "Mastodon".to_string(), false, vec![
+            NormalizationRule::Trim,
+            NormalizationRule::StripPrefix("@".to_string()),
+            NormalizationRule::Lowercase,
+        ], None);
+        contract.add_verifier(master_valid_account(), vec![CATEGORY_TELEGRAM.to_string(), "Mastodon".to_string()]);
+
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].contains(r#""event":"category_added","data":[{"category_id":"Mastodon""#), "Wrong event {}", logs[0]);
+
+        let contact = Contact {
+            category: "Mastodon".to_string(),
+            value: " @Alice@Mastodon.Social".to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        let contact_hash = contract.get_contact_hash(contact.clone());
+        assert_eq!(contact_hash, digest("Mastodon:alice@mastodon.social".to_string()), "Wrong normalization");
+
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), "Mastodon".to_string(), Some(contact_hash));
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+        contract.start_auth(alice_request_key(), contact.clone(), None);
+        assert!(contract.confirm_auth(alice_secret_key()), "Request wasn't confirmed");
+
+        assert_eq!(contract.get_account_for_contact_stringified("Mastodon:alice@mastodon.social".to_string()), Some(alice_account()), "Contact wasn't bound");
+        assert!(contract.is_owner(alice_account(), Contact { value: "alice@mastodon.social".to_string(), ..contact }), "Wrong owner");
+    }
+
+    #[test]
+    fn deprecate_category() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(alice_secret_key());
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.deprecate_category(CATEGORY_TELEGRAM.to_string());
+
+        assert!(contract.get_category(CATEGORY_TELEGRAM.to_string()).unwrap().deprecated, "Category wasn't deprecated");
+        assert_eq!(contract.get_account_for_contact(alice_contact()), Some(alice_account()), "Bound contact wasn't kept");
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        assert!(contract.remove(alice_contact()), "Contact wasn't removed");
+        assert_eq!(contract.get_account_for_contact(alice_contact()), None, "Contact wasn't removed");
+    }
+
+    #[test]
+    fn validate_email() {
+        let cases = vec![
+            ("alice@example.com", Ok(())),
+            ("alice.bob+tag@mail.example.co", Ok(())),
+            ("o'hara@xn--80ak6aa92e.com", Ok(())),
+            ("alice", Err(InvalidContactReason::EmailMissingAt)),
+            ("https://example.com", Err(InvalidContactReason::EmailMissingAt)),
+            ("@example.com", Err(InvalidContactReason::EmailLocalPartEmpty)),
+            ("ali ce@example.com", Err(InvalidContactReason::InvalidCharacter)),
+            ("\"alice\"@example.com", Err(InvalidContactReason::InvalidCharacter)),
+            ("alice@bob@example.com", Err(InvalidContactReason::InvalidCharacter)),
+            (".alice@example.com", Err(InvalidContactReason::EmailLocalPartInvalidDot)),
+            ("alice.@example.com", Err(InvalidContactReason::EmailLocalPartInvalidDot)),
+            ("alice..bob@example.com", Err(InvalidContactReason::EmailLocalPartInvalidDot)),
+            ("alice@localhost", Err(InvalidContactReason::EmailDomainNotQualified)),
+            ("alice@-example.com", Err(InvalidContactReason::EmailDomainLabelInvalid)),
+            ("alice@example-.com", Err(InvalidContactReason::EmailDomainLabelInvalid)),
+            ("alice@example..com", Err(InvalidContactReason::EmailDomainLabelInvalid)),
+            ("alice@exa_mple.com", Err(InvalidContactReason::EmailDomainLabelInvalid)),
+            ("alice@[127.0.0.1]", Err(InvalidContactReason::EmailDomainLabelInvalid)),
+        ];
+        for (value, result) in cases {
+            assert_eq!(ContactValidator::Email.validate(value), result, "Wrong result for {}", value);
+        }
+
+        let local_part = "a".repeat(64);
+        assert_eq!(ContactValidator::Email.validate(&format!("{}@example.com", local_part)), Ok(()), "Local part of 64 chars is valid");
+        assert_eq!(ContactValidator::Email.validate(&format!("a{}@example.com", local_part)), Err(InvalidContactReason::EmailLocalPartTooLong), "Local part is too long");
+        assert_eq!(ContactValidator::Email.validate(&format!("alice@{}.com", "a".repeat(64))), Err(InvalidContactReason::EmailDomainLabelInvalid), "Domain label is too long");
+        assert_eq!(ContactValidator::Email.validate(&format!("{}@{}", local_part, vec!["a".repeat(63); 3].join("."))), Err(InvalidContactReason::TooLong), "Address is too long");
+    }
+
+    #[test]
+    fn validate_github() {
+        let cases = vec![
+            ("octocat".to_string(), Ok(())),
+            ("a".to_string(), Ok(())),
+            ("octo-cat-1".to_string(), Ok(())),
+            ("a".repeat(39), Ok(())),
+            ("a".repeat(40), Err(InvalidContactReason::TooLong)),
+            ("octo_cat".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            ("octo cat".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            ("https://github.com/octocat".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            ("-octocat".to_string(), Err(InvalidContactReason::InvalidFirstCharacter)),
+            ("octocat-".to_string(), Err(InvalidContactReason::InvalidLastCharacter)),
+            ("octo--cat".to_string(), Err(InvalidContactReason::ConsecutiveSpecialCharacters)),
+        ];
+        for (value, result) in cases {
+            assert_eq!(ContactValidator::Github.validate(&value), result, "Wrong result for {}", value);
+        }
+    }
+
+    #[test]
+    fn validate_twitter() {
+        let cases = vec![
+            ("jack".to_string(), Ok(())),
+            ("near_protocol".to_string(), Ok(())),
+            ("a".repeat(15), Ok(())),
+            ("a".repeat(16), Err(InvalidContactReason::TooLong)),
+            ("@jack".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            ("jack.doe".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            ("x.com/jack".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+        ];
+        for (value, result) in cases {
+            assert_eq!(ContactValidator::Twitter.validate(&value), result, "Wrong result for {}", value);
+        }
+    }
+
+    #[test]
+    fn validate_telegram() {
+        let cases = vec![
+            ("account_123".to_string(), Ok(())),
+            ("alice".to_string(), Ok(())),
+            ("a".repeat(32), Ok(())),
+            ("alic".to_string(), Err(InvalidContactReason::TooShort)),
+            ("a".repeat(33), Err(InvalidContactReason::TooLong)),
+            ("alice-bob".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            ("t.me/alice".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            ("1alice".to_string(), Err(InvalidContactReason::InvalidFirstCharacter)),
+            ("_alice".to_string(), Err(InvalidContactReason::InvalidFirstCharacter)),
+            ("alice_".to_string(), Err(InvalidContactReason::InvalidLastCharacter)),
+        ];
+        for (value, result) in cases {
+            assert_eq!(ContactValidator::Telegram.validate(&value), result, "Wrong result for {}", value);
+        }
+    }
+
+    #[test]
+    fn validate_forum() {
+        let cases = vec![
+            ("alice".to_string(), Ok(())),
+            ("a.b_c-d".to_string(), Ok(())),
+            ("_alice".to_string(), Ok(())),
+            ("a".repeat(20), Ok(())),
+            ("al".to_string(), Err(InvalidContactReason::TooShort)),
+            ("a".repeat(21), Err(InvalidContactReason::TooLong)),
+            ("alice!".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            ("alice bob".to_string(), Err(InvalidContactReason::InvalidCharacter)),
+            (".alice".to_string(), Err(InvalidContactReason::InvalidFirstCharacter)),
+            ("alice_".to_string(), Err(InvalidContactReason::InvalidLastCharacter)),
+            ("alice..bob".to_string(), Err(InvalidContactReason::ConsecutiveSpecialCharacters)),
+            ("alice_-bob".to_string(), Err(InvalidContactReason::ConsecutiveSpecialCharacters)),
+        ];
+        for (value, result) in cases {
+            assert_eq!(ContactValidator::Forum.validate(&value), result, "Wrong result for {}", value);
+        }
+    }
+
+    #[test]
+    fn validate_phone() {
+        let cases = vec![
+            ("+14155552671", Ok(())),
+            ("+447700900123", Ok(())),
+            ("+393123456", Ok(())),
+            ("+8613800138000", Ok(())),
+            ("+971501234567", Ok(())),
+            ("+35799123456", Ok(())),
+            ("+375291234567", Ok(())),
+            ("+593991234567", Ok(())),
+            ("+233241234567", Ok(())),
+            ("+9647701234567", Ok(())),
+            ("14155552671", Err(InvalidContactReason::PhoneMissingCountryCode)),
+            ("+1415555267a", Err(InvalidContactReason::InvalidCharacter)),
+            ("+1 415 555 2671", Err(InvalidContactReason::InvalidCharacter)),
+            ("+1415555267", Err(InvalidContactReason::TooShort)),
+            ("+141555526710", Err(InvalidContactReason::TooLong)),
+            ("+4477009001234", Err(InvalidContactReason::TooLong)),
+            ("+9715012345678901", Err(InvalidContactReason::TooLong)),
+            ("+0123456789", Err(InvalidContactReason::PhoneCountryCodeUnknown)),
+            ("+357123", Err(InvalidContactReason::TooShort)),
+        ];
+        for (value, result) in cases {
+            assert_eq!(ContactValidator::Phone.validate(value), result, "Wrong result for {}", value);
+        }
+    }
+
+    #[test]
+    fn validate_discord() {
+        let cases = vec![
+            ("alice", Ok(())),
+            ("alice.near_42", Ok(())),
+            ("alice#0042", Ok(())),
+            ("alice smith#1234", Ok(())),
+            ("a", Err(InvalidContactReason::TooShort)),
+            ("alice-near", Err(InvalidContactReason::InvalidCharacter)),
+            ("alice..near", Err(InvalidContactReason::ConsecutiveSpecialCharacters)),
+            ("here", Err(InvalidContactReason::DiscordNameReserved)),
+            ("alice#123", Err(InvalidContactReason::DiscordDiscriminatorInvalid)),
+            ("alice#0000", Err(InvalidContactReason::DiscordDiscriminatorInvalid)),
+            ("alice#12a4", Err(InvalidContactReason::DiscordDiscriminatorInvalid)),
+            ("a#1234", Err(InvalidContactReason::TooShort)),
+            ("ali:ce#1234", Err(InvalidContactReason::InvalidCharacter)),
+            ("everyone#1234", Err(InvalidContactReason::DiscordNameReserved)),
+        ];
+        for (value, result) in cases {
+            assert_eq!(ContactValidator::Discord.validate(value), result, "Wrong result for {}", value);
+        }
+        assert_eq!(ContactValidator::Discord.validate(&"a".repeat(33)), Err(InvalidContactReason::TooLong), "Long username was accepted");
+    }
+
+    #[test]
+    fn discord_contact() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+        let discord_contact = |value: &str| Contact {
+            category: CATEGORY_DISCORD.to_string(),
+            value: value.to_string(),
+            account_id: Some(U64(80351110224678912)),
+            display_value: None,
+        };
+        assert_eq!(contract.get_contact_hash(discord_contact(" @Alice#0042")), digest("Discord:80351110224678912".to_string()), "Contact isn't keyed by snowflake id");
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_DISCORD.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), discord_contact("Alice#0042"), None);
+        contract.confirm_auth(alice_secret_key());
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.update_contact_display(alice_valid_account(), CATEGORY_DISCORD.to_string(), U64(80351110224678912), "alice.near".to_string());
+
+        assert!(contract.is_owner(alice_account(), discord_contact("old_name")), "Contact isn't identified by snowflake id");
+        assert_eq!(contract.get_contacts_by_type(alice_account(), CATEGORY_DISCORD.to_string()), Some(vec!["alice.near".to_string()]), "Username wasn't updated");
+    }
+
+    #[test]
+    fn e164_phone() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        let phone_contact = |value: &str| Contact {
+            category: CATEGORY_PHONE.to_string(),
+            value: value.to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        let cases = vec![
+            ("+1 (415) 555-2671", "+14155552671"),
+            ("0044 7700 900123", "+447700900123"),
+            ("+44.7700.900.123", "+447700900123"),
+            ("＋１ ４１５ ５５５ ２６７１", "+14155552671"),
+        ];
+        for (value, e164_value) in cases {
+            assert_eq!(contract.get_contact_hash(phone_contact(value)), digest(format!("Phone:{}", e164_value)), "Wrong E.164 value of {}", value);
+        }
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.set_normalization_rules(CATEGORY_PHONE.to_string(), vec![NormalizationRule::Nfkc, NormalizationRule::E164(Some(1))]);
+        assert_eq!(contract.get_contact_hash(phone_contact("(415) 555-2671")), digest("Phone:+14155552671".to_string()), "Default country code wasn't applied");
+        assert_eq!(contract.get_contact_hash(phone_contact("+44 7700 900123")), digest("Phone:+447700900123".to_string()), "Default country code was applied to international number");
+
+        contract.set_normalization_rules(CATEGORY_PHONE.to_string(), vec![NormalizationRule::Nfkc, NormalizationRule::E164(Some(49))]);
+        assert_eq!(contract.get_contact_hash(phone_contact("0151 23456789")), digest("Phone:+4915123456789".to_string()), "Trunk prefix wasn't dropped");
+
+        contract.set_normalization_rules(CATEGORY_PHONE.to_string(), vec![NormalizationRule::Nfkc, NormalizationRule::E164(Some(39))]);
+        assert_eq!(contract.get_contact_hash(phone_contact("06 1234 5678")), digest("Phone:+390612345678".to_string()), "Italian leading zero was dropped");
+    }
+
+    #[test]
+    fn bind_phone_contact() {
+        let mut contract = get_contract_with_verifier_key();
+        let phone_contact = Contact {
+            category: CATEGORY_PHONE.to_string(),
+            value: "+1 415 555 2671".to_string(),
+            account_id: None,
+            display_value: None,
+        };
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        let signature = sign_claim(alice_account(), "Phone:+14155552671".to_string(), "+14155552671", 100, 1);
+        contract.claim_contact(phone_contact.clone(), U64(100), U64(1), signature, None);
+
+        let records = contract.get_contacts(alice_account()).unwrap();
+        assert!(records[0].is_private, "Phone contact isn't private by default");
+        assert!(records[0].contact.display_value.is_none(), "Display value of the private contact was saved");
+        assert_eq!(contract.get_account_for_contact(phone_contact), Some(alice_account()), "Phone contact wasn't found");
+    }
+
+    #[test]
+    fn canonical_email() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let contract = Contract::new(master_valid_account());
+
+        let email_contact = |value: &str| Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: value.to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        let cases = vec![
+            ("John.Doe+near@GMail.com", "johndoe@gmail.com"),
+            ("john.doe@googlemail.com", "johndoe@gmail.com"),
+            ("john.doe+near@example.com", "john.doe+near@example.com"),
+            ("alice@Bücher.de", "alice@xn--bcher-kva.de"),
+            ("alice@ｅｘａｍｐｌｅ.com", "alice@example.com"),
+        ];
+        for (value, canonical_value) in cases {
+            assert_eq!(contract.get_contact_hash(email_contact(value)), digest(format!("Email:{}", canonical_value)), "Wrong canonical value of {}", value);
+        }
+    }
+
+    #[test]
+    fn bind_canonical_email() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_email_request_key(), CATEGORY_EMAIL.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        let email_contact = |value: &str| Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: value.to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        contract.start_auth(alice_email_request_key(), email_contact(" Alice.Doe+near@gmail.com"), None);
+        contract.confirm_auth(alice_email_secret_key());
+
+        assert_eq!(contract.get_account_for_contact_stringified("Email:alicedoe@gmail.com".to_string()), Some(alice_account()), "Canonical value wasn't used");
+        assert_eq!(contract.get_account_for_contact(email_contact("alicedoe@googlemail.com")), Some(alice_account()), "Alias wasn't found");
+        assert_eq!(contract.get_contacts_by_type(alice_account(), CATEGORY_EMAIL.to_string()), Some(vec!["Alice.Doe+near@gmail.com".to_string()]), "Original value wasn't kept");
+        assert!(contract.is_owner(alice_account(), email_contact("alice.doe@gmail.com")), "Wrong owner");
+
+        assert!(contract.remove(email_contact("ALICEDOE@gmail.com")), "Contact wasn't removed");
+        assert_eq!(contract.get_account_for_contact(email_contact("alicedoe@gmail.com")), None, "Contact wasn't removed");
+    }
+
+    #[test]
+    fn set_email_domain_rule() {
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        let email_contact = Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: "alice+near@proton.me".to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        assert_eq!(contract.get_contact_hash(email_contact.clone()), digest("Email:alice+near@proton.me".to_string()), "Unknown domain was changed");
+
+        contract.set_email_domain_rule("proton.me".to_string(), EmailDomainRule {
+            strip_dots: false,
+            strip_plus_tag: true,
+            alias_of: None,
+        });
+        assert_eq!(contract.get_contact_hash(email_contact.clone()), digest("Email:alice@proton.me".to_string()), "Rule wasn't applied");
+
+        contract.remove_email_domain_rule("proton.me".to_string());
+        assert_eq!(contract.get_email_domain_rules().len(), 2, "Rule wasn't removed");
+        assert_eq!(contract.get_contact_hash(email_contact), digest("Email:alice+near@proton.me".to_string()), "Rule wasn't removed");
+
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].contains(r#""event":"email_domain_rule_set","data":[{"domain":"proton.me","rule":{"strip_dots":false,"strip_plus_tag":true,"alias_of":null}}]"#), "Wrong event {}", logs[0]);
+        assert!(logs[1].contains(r#""event":"email_domain_rule_removed","data":[{"domain":"proton.me"}]"#), "Wrong event {}", logs[1]);
+    }
+
+    #[test]
+    fn nfkc_contact() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_NEAR_GOV_FORUM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        let forum_contact = |value: &str| Contact {
+            category: CATEGORY_NEAR_GOV_FORUM.to_string(),
+            value: value.to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        contract.start_auth(alice_request_key(), forum_contact("ａｌｉｃｅ"), None);
+        contract.confirm_auth(alice_secret_key());
+
+        assert_eq!(contract.get_account_for_contact_stringified("NearGovForum:alice".to_string()), Some(alice_account()), "NFKC wasn't applied");
+        assert_eq!(contract.get_account_for_contact(forum_contact("ＡＬＩＣＥ")), Some(alice_account()), "NFKC wasn't applied to lookup");
+        assert!(contract.contact_skeletons.contains_key(&"NearGovForum:alice".to_string()), "Skeleton wasn't saved");
+
+        contract.remove(forum_contact("alice"));
+        assert!(!contract.contact_skeletons.contains_key(&"NearGovForum:alice".to_string()), "Skeleton wasn't removed");
+    }
+
+    #[test]
+    fn contact_skeleton() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let contract = Contract::new(master_valid_account());
+
+        let skeleton = |category: &str, value: &str| contract.get_contact_skeleton(&Contact {
+            category: category.to_string(),
+            value: value.to_string(),
+            account_id: Some(U64(1)),
+            display_value: None,
+        });
+        assert_eq!(skeleton(CATEGORY_NEAR_GOV_FORUM, "alice"), skeleton(CATEGORY_NEAR_GOV_FORUM, "\u{430}lice"), "Cyrillic a isn't confusable");
+        assert_eq!(skeleton(CATEGORY_NEAR_GOV_FORUM, "modern"), skeleton(CATEGORY_NEAR_GOV_FORUM, "rnodern"), "rn isn't confusable with m");
+        assert_eq!(skeleton(CATEGORY_NEAR_GOV_FORUM, "paypal"), skeleton(CATEGORY_NEAR_GOV_FORUM, "paypa1"), "1 isn't confusable with l");
+        assert_ne!(skeleton(CATEGORY_NEAR_GOV_FORUM, "alice"), skeleton(CATEGORY_NEAR_GOV_FORUM, "bob"), "Different contacts are confusable");
+        assert_ne!(skeleton(CATEGORY_NEAR_GOV_FORUM, "alice"), skeleton(CATEGORY_EMAIL, "alice"), "Contacts of different categories are confusable");
+        assert_eq!(skeleton(CATEGORY_TELEGRAM, "alice"), None, "Contacts with stable id don't have skeletons");
+    }
+
+    #[test]
+    fn set_contact_stable_id() {
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        // contact bound by handle before GitHub required stable id
+        let legacy_contact = Contact {
+            category: CATEGORY_GITHUB.to_string(),
+            value: "octocat".to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        contract.insert_accounts_for_contact(alice_account(), legacy_contact.clone());
+        let legacy_record = |contact: Contact| ContactRecord {
+            contact,
+            verified_at: 0,
+            verified_by: master_account(),
+            verification_method: VerificationMethod::Migration,
+            is_primary: false,
+            is_private: false,
+        };
+        contract.save_contacts(&alice_account(), vec![legacy_record(alice_contact()), legacy_record(legacy_contact.clone())]);
+        assert_eq!(contract.get_account_for_contact(legacy_contact.clone()), Some(alice_account()), "Legacy contact wasn't found by handle");
+
+        contract.set_contact_stable_id(alice_valid_account(), CATEGORY_GITHUB.to_string(), "OctoCat".to_string(), U64(583231));
+
+        assert_eq!(contract.get_account_for_contact_stringified("Github:octocat".to_string()), None, "Handle key wasn't removed");
+        assert_eq!(contract.get_account_for_contact_stringified("Github:583231".to_string()), Some(alice_account()), "Stable id key wasn't added");
+        assert_eq!(contract.get_account_for_contact(Contact {
+            value: "octocat-renamed".to_string(),
+            account_id: Some(U64(583231)),
+            ..legacy_contact.clone()
+        }), Some(alice_account()), "Contact wasn't found by stable id");
+
+        let contacts = contract.get_contacts(alice_account()).unwrap();
+        assert_eq!(contacts.len(), 2, "Wrong number of contacts");
+        assert_eq!(contacts[1].contact.value, "octocat", "Handle wasn't kept");
+        assert_eq!(contacts[1].contact.account_id, Some(U64(583231)), "Stable id wasn't set");
+
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].contains(r#""event":"contact_stable_id_set","data":[{"account_id":"alice.near","contact":{"category":"Github","value":"octocat","account_id":"583231"}}]"#), "Wrong event {}", logs[0]);
+    }
+
+    #[test]
+    fn update_contact_display() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(alice_secret_key());
+        let storage_paid = contract.storage_paid(alice_valid_account()).0;
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.update_contact_display(alice_valid_account(), CATEGORY_TELEGRAM.to_string(), U64(1), "@Account_123_Renamed".to_string());
+
+        let contacts = contract.get_contacts(alice_account()).unwrap();
+        assert_eq!(contacts[0].contact.value, "account_123_renamed", "Value wasn't updated");
+        assert_eq!(contacts[0].contact.display_value, Some("@Account_123_Renamed".to_string()), "Display value wasn't updated");
+        assert_eq!(contract.get_account_for_contact(alice_contact()), Some(alice_account()), "Contact key was changed");
+        assert!(contract.storage_paid(alice_valid_account()).0 < storage_paid, "Storage wasn't charged");
+
+        let logs = near_sdk::test_utils::get_logs();
+        assert!(logs[0].contains(r#""event":"contact_display_updated","data":[{"account_id":"alice.near","contact":{"category":"Telegram","value":"account_123_renamed","account_id":"1","display_value":"@Account_123_Renamed"},"previous_value":"account_123"}]"#), "Wrong event {}", logs[0]);
+    }
+
+    #[test]
+    fn contact_verification() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.add_verifier(telegram_bot_valid_account(), vec![CATEGORY_TELEGRAM.to_string()]);
+
+// switch to a context with verifier
+        let context = get_context(telegram_bot_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let mut context = get_context(alice_account(), 1, false);
+        context.block_timestamp = 42;
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.confirm_auth(alice_secret_key());
+
+        let contacts = contract.get_contacts(alice_account()).unwrap();
+        assert_eq!(contacts[0].verified_at, 42, "Wrong verification time");
+        assert_eq!(contacts[0].verified_by, telegram_bot_account(), "Wrong verifier");
+        assert_eq!(contacts[0].verification_method, VerificationMethod::SecretKey, "Wrong verification method");
+        assert_eq!(
+            near_sdk::serde_json::to_string(&contacts[0]).unwrap(),
+            r#"{"category":"Telegram","value":"account_123","account_id":"1","verified_at":42,"verified_by":"telegram_bot.near","verification_method":"secret_key","is_primary":false,"is_private":false}"#,
+            "Wrong view of the contact"
+        );
+    }
+
+    #[test]
+    fn stale_contact() {
+        let mut contract = get_contract_with_expiring_contact();
+
+        let mut context = get_context(alice_account(), 0, true);
+        context.block_timestamp = 100;
+        testing_env!(context.clone());
+        assert!(contract.is_owner(alice_account(), alice_contact()), "Contact is stale before expiration");
+
+        context.block_timestamp = 101;
+        testing_env!(context.clone());
+        assert!(!contract.is_owner(alice_account(), alice_contact()), "Stale contact is reported as owned");
+        assert_eq!(contract.get_account_for_contact(alice_contact()), Some(alice_account()), "Stale contact was unbound");
+
+// switch to a context with verifier
+        let mut context = get_context(telegram_bot_account(), 0, false);
+        context.block_timestamp = 150;
+        testing_env!(context.clone());
+        contract.reverify_contact(alice_valid_account(), alice_contact());
+
+        let mut context = get_context(alice_account(), 0, true);
+        context.block_timestamp = 250;
+        testing_env!(context.clone());
+        assert!(contract.is_owner(alice_account(), alice_contact()), "Contact wasn't reverified");
+
+        let contacts = contract.get_contacts(alice_account()).unwrap();
+        assert_eq!(contacts.len(), 1, "Wrong number of contacts");
+        assert_eq!(contacts[0].verified_at, 150, "Wrong verification time");
+        assert_eq!(contacts[0].verification_method, VerificationMethod::Reverification, "Wrong verification method");
+    }
+
+    #[test]
+    fn remove_stale_contact() {
+        let mut contract = get_contract_with_expiring_contact();
+
+        let mut context = get_context(alice_account(), 0, false);
+        context.block_timestamp = 101;
+        testing_env!(context.clone());
+        assert!(contract.remove(alice_contact()), "Stale contact wasn't removed");
+        assert!(contract.get_account_for_contact(alice_contact()).is_none(), "Stale contact wasn't unbound");
+    }
+
+    #[test]
+    fn primary_contact() {
+        let mut contract = get_contract_with_verifier_key();
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        let work_email_contact = Contact { value: "alice@work.com".to_string(), ..alice_email_contact() };
+        for (nonce, contact) in vec![alice_contact(), alice_email_contact(), work_email_contact.clone()].into_iter().enumerate() {
+            let signature = sign_claim(alice_account(), contract.get_contact_stringified(contact.clone()), &contact.value, 100, nonce as u64);
+            contract.claim_contact(contact, U64(100), U64(nonce as u64), signature, None);
+        }
+
+        let primary_email = contract.get_primary_contact(alice_account(), CATEGORY_EMAIL.to_string()).unwrap();
+        assert_eq!(primary_email.contact.value, "alice@example.com", "First contact of the category isn't primary by default");
+        assert_eq!(
+            contract.resolve_display_identity(alice_account()),
+            Some(DisplayIdentity { category: CATEGORY_TELEGRAM.to_string(), handle: "account_123".to_string() }),
+            "First contact isn't preferred by default"
+        );
+
+        contract.set_primary_contact(work_email_contact.clone());
+        let primary_email = contract.get_primary_contact(alice_account(), CATEGORY_EMAIL.to_string()).unwrap();
+        assert_eq!(primary_email.contact.value, "alice@work.com", "Primary contact wasn't set");
+        assert_eq!(
+            contract.resolve_display_identity(alice_account()),
+            Some(DisplayIdentity { category: CATEGORY_EMAIL.to_string(), handle: "alice@work.com".to_string() }),
+            "Last primary contact isn't preferred"
+        );
+
+        contract.set_primary_contact(alice_email_contact());
+        let primary_emails: Vec<String> = contract.get_contacts(alice_account()).unwrap().into_iter()
+            .filter(|record| record.is_primary)
+            .map(|record| record.contact.value)
+            .collect();
+        assert_eq!(primary_emails, vec!["alice@example.com".to_string()], "Previous primary contact wasn't reset");
+        assert!(contract.get_primary_contact(alice_account(), CATEGORY_GITHUB.to_string()).is_none(), "Primary contact of unused category");
+    }
+
+    #[test]
+    fn private_contact() {
+        let mut contract = get_contract_with_verifier_key();
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        let signature = sign_claim(alice_account(), "Email:alice@example.com".to_string(), "alice@example.com", 100, 1);
+        contract.claim_contact(alice_email_contact(), U64(100), U64(1), signature, Some(true));
+
+        assert!(!near_sdk::test_utils::get_logs().iter().any(|log| log.contains("alice@example.com")), "Private contact was logged");
+        assert_eq!(contract.get_account_for_contact(alice_email_contact()), Some(alice_account()), "Private contact wasn't found");
+        assert!(contract.is_owner(alice_account(), alice_email_contact()), "Private contact isn't owned");
+
+        let records = contract.get_contacts(alice_account()).unwrap();
+        assert!(records[0].is_private, "Contact isn't private");
+        assert_ne!(records[0].contact.value, "alice@example.com", "Private contact was saved as plain text");
+        assert!(!contract.get_all_contacts(0, 10).items.contains_key(&alice_account()), "Private contact was enumerated");
+        assert!(contract.get_all_contacts_by_type(CATEGORY_EMAIL.to_string(), 0, 10).items.is_empty(), "Private contact was enumerated by type");
+        assert!(contract.get_all_accounts_for_contacts(0, 10).items.keys().all(|key| !key.contains("alice@example.com")), "Private contact key was enumerated");
+
+        assert!(contract.remove(alice_email_contact()), "Private contact wasn't removed");
+        assert!(contract.get_account_for_contact(alice_email_contact()).is_none(), "Private contact wasn't unbound");
+    }
+
+    #[test]
+    fn discovery_settings() {
+        let mut contract = get_contract_with_verifier_key();
+        let carol_account = "carol.near".to_string();
+        let carol_email_contact = Contact { value: "carol@example.com".to_string(), ..alice_email_contact() };
+
+        let claims = vec![
+            (alice_account(), alice_contact()),
+            (alice_account(), alice_email_contact()),
+            (bob_account(), bob_contact()),
+            (carol_account.clone(), carol_email_contact.clone()),
+        ];
+        for (nonce, (account_id, contact)) in claims.into_iter().enumerate() {
+            let mut context = get_context(account_id.clone(), ntoy(1), false);
+            context.current_account_id = alice_account();
+            testing_env!(context.clone());
+            contract.storage_deposit(None);
+            let signature = sign_claim(account_id, contract.get_contact_stringified(contact.clone()), &contact.value, 100, nonce as u64);
+            contract.claim_contact(contact, U64(100), U64(nonce as u64), signature, None);
+        }
+
+        let context = get_context(bob_account(), 0, false);
+        testing_env!(context.clone());
+        contract.set_discovery_settings(true, vec![], vec![]);
+
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        contract.set_discovery_settings(false, vec![CATEGORY_EMAIL.to_string()], vec![alice_contact()]);
+        assert!(contract.get_discovery_settings(alice_account()).hidden_contacts == vec![alice_contact()], "Hidden contact wasn't saved");
+
+        // alice has no discoverable contacts left, bob is hidden
+        let page = contract.get_all_contacts(0, 1);
+        assert_eq!(page.items.keys().collect::<Vec<_>>(), vec![&carol_account], "Hidden accounts weren't skipped");
+        assert_eq!(page.next_index, None, "Wrong next index of the last page");
+        assert_eq!(contract.get_all_accounts_for_contacts(0, 10).items.len(), 1, "Hidden contacts were enumerated");
+        assert_eq!(contract.get_all_contacts_by_type(CATEGORY_EMAIL.to_string(), 0, 10).items.len(), 1, "Hidden category was enumerated");
+        assert_eq!(contract.get_account_for_contact(alice_email_contact()), Some(alice_account()), "Hidden contact isn't found by lookup");
+        assert_eq!(contract.get_account_for_contact(bob_contact()), Some(bob_account()), "Contact of hidden account isn't found by lookup");
+
+        contract.set_discovery_settings(false, vec![], vec![alice_contact()]);
+        let first_page = contract.get_all_contacts(0, 1);
+        assert_eq!(first_page.items.keys().collect::<Vec<_>>(), vec![&alice_account()], "Wrong first page of contacts");
+        assert_eq!(first_page.next_index, Some(U64(1)), "Wrong next index");
+        assert_eq!(contract.get_all_contacts(1, 2).items.keys().collect::<Vec<_>>(), vec![&carol_account], "Wrong second page of contacts");
+        assert_eq!(contract.get_all_contacts(0, 10).items[&alice_account()].len(), 1, "Hidden contact was enumerated");
+    }
+
+    #[test]
+    fn migrate_from_version_1() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        write_version_1_state(
+            vec![(alice_account(), vec![
+                ContactV1 { category: ContactCategories::Telegram, value: "account_123".to_string(), account_id: Some(1) },
+                ContactV1 { category: ContactCategories::Email, value: "alice@example.com".to_string(), account_id: None },
+            ])],
+            vec![(digest(bob_secret_key()), RequestV1 { contact: None, account_id: bob_account() })],
+        );
+
+        let mut contract = Contract::migrate_state_25();
+
+        assert_eq!(contract.get_version(), 25, "Wrong version");
+        assert!(contract.is_verifier(master_account(), CATEGORY_DISCORD.to_string()), "Default categories weren't registered");
+        assert!(contract.is_owner(alice_account(), alice_contact()), "Telegram contact wasn't converted on read");
+        assert!(contract.is_owner(alice_account(), alice_email_contact()), "Email contact wasn't converted on read");
+        assert_eq!(contract.get_account_for_contact(alice_email_contact()), Some(alice_account()), "Contact index wasn't kept");
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        assert_eq!(contract.migrate_legacy_entries(1), U64(1), "Wrong number of entries left");
+        assert_eq!(contract.migrate_legacy_entries(1), U64(0), "Wrong number of entries left");
+
+        let records = contract.get_contacts(alice_account()).unwrap();
+        assert_eq!(records.len(), 2, "Contacts weren't moved");
+        assert_eq!(records[0].verification_method, VerificationMethod::Migration, "Wrong verification method");
+        assert_eq!(contract.get_all_contacts(0, 10).items.len(), 1, "Moved account isn't listed");
+        assert_eq!(contract.storage_paid(bob_valid_account()).0, WHITELIST_STORAGE_COST, "Whitelist storage cost wasn't refunded");
+    }
+
+    #[test]
+    fn migrate_legacy_emails_to_canonical_values() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let carol_account = "carol.near".to_string();
+        let email_contact = |value: &str| Contact { value: value.to_string(), ..alice_email_contact() };
+        let legacy_email_contact = |value: &str| ContactV1 { category: ContactCategories::Email, value: value.to_string(), account_id: None };
+        write_version_1_state(vec![
+            (alice_account(), vec![legacy_email_contact("john.doe@gmail.com")]),
+            (bob_account(), vec![legacy_email_contact("johndoe@gmail.com")]),
+            (carol_account.clone(), vec![legacy_email_contact("Carol.Smith@googlemail.com")]),
+        ], vec![]);
+        let mut contract = Contract::migrate_state_25();
+
+        assert!(contract.is_owner(carol_account.clone(), email_contact("carolsmith@gmail.com")), "Contact wasn't normalized on read");
+        assert_eq!(contract.get_account_for_contact(email_contact("Carol.Smith@googlemail.com")), Some(carol_account.clone()), "Saved value wasn't found");
+        assert_eq!(contract.get_contacts(alice_account()).unwrap()[0].contact.value, "john.doe@gmail.com", "Contact bound by another account was normalized");
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        assert_eq!(contract.migrate_legacy_entries(10), U64(0), "Wrong number of entries left");
+
+        assert_eq!(contract.get_account_for_contact(email_contact("carolsmith@gmail.com")), Some(carol_account.clone()), "Contact wasn't moved to the canonical key");
+        assert_eq!(contract.get_account_for_contact_stringified("Email:Carol.Smith@googlemail.com".to_string()), None, "Saved key wasn't removed");
+        assert_eq!(contract.get_account_for_contact(email_contact("john.doe@gmail.com")), Some(bob_account()), "Canonical key was rebound");
+        let record = &contract.get_contacts(carol_account.clone()).unwrap()[0];
+        assert_eq!(record.contact.display_value, Some("Carol.Smith@googlemail.com".to_string()), "Saved value wasn't kept");
+
+        let context = get_context(carol_account.clone(), 1, false);
+        testing_env!(context.clone());
+        assert!(contract.remove(email_contact("Carol.Smith@gmail.com")), "Contact wasn't removed");
+        assert_eq!(contract.get_account_for_contact(email_contact("carolsmith@gmail.com")), None, "Contact is still bound");
+    }
+
+    #[test]
+    fn error_codes() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let contract = Contract::new(master_valid_account());
+
+        let error_codes = contract.get_error_codes();
+        assert_eq!(error_codes.len(), ContractError::all().len(), "Wrong number of error codes");
+
+        let codes: std::collections::HashSet<&str> = error_codes.iter().map(|error| error.code).collect();
+        assert_eq!(codes.len(), error_codes.len(), "Error codes are not unique");
+        assert!(codes.iter().all(|code| code.starts_with("ERR_")), "Wrong error code format");
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_PRIVATE_FUNCTION:")]
+    fn migrate_by_user() {
+        let mut context = get_context(bob_account(), 0, false);
+        context.current_account_id = alice_account();
+        testing_env!(context.clone());
+
+        Contract::migrate_state_25();
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_OLD_STATE_NOT_FOUND:")]
+    fn migrate_without_state() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        Contract::migrate_state_25();
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_CONFUSABLE:")]
+    fn claim_contact_confusable_with_saved_legacy_contact() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        write_version_1_state(vec![(alice_account(), vec![
+            ContactV1 { category: ContactCategories::NearGovForum, value: "mary".to_string(), account_id: None },
+        ])], vec![]);
+        let mut contract = Contract::migrate_state_25();
+
+        let context = get_context(bob_account(), ntoy(100), false);
+        testing_env!(context.clone());
+        contract.storage_deposit(Some(bob_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.add_verifier_key(verifier_public_key(), vec![CATEGORY_NEAR_GOV_FORUM.to_string()]);
+
+// alice's contacts are saved when she marks a primary contact
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+        contract.storage_deposit(Some(alice_valid_account()));
+        contract.set_primary_contact(Contact {
+            category: CATEGORY_NEAR_GOV_FORUM.to_string(),
+            value: "mary".to_string(),
+            account_id: None,
+            display_value: None,
+        });
+
+        let confusable_contact = Contact {
+            category: CATEGORY_NEAR_GOV_FORUM.to_string(),
+            value: "rnary".to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        let context = get_context(bob_account(), 0, false);
+        testing_env!(context.clone());
+        let signature = sign_claim(bob_account(), contract.get_contact_stringified(contract.prepare_contact(confusable_contact.clone())), "rnary", 100, 1);
+        contract.claim_contact(confusable_contact, U64(100), U64(1), signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_MIGRATION_IN_PROGRESS:")]
+    fn claim_contact_before_legacy_entries_are_moved() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        write_version_1_state(vec![(alice_account(), vec![
+            ContactV1 { category: ContactCategories::Email, value: "john.doe@gmail.com".to_string(), account_id: None },
+        ])], vec![]);
+        let mut contract = Contract::migrate_state_25();
+
+        let bob_email_contact = Contact { value: "johndoe@gmail.com".to_string(), ..alice_email_contact() };
+        let context = get_context(bob_account(), 0, false);
+        testing_env!(context.clone());
+        let signature = sign_claim(bob_account(), "Email:johndoe@gmail.com".to_string(), "johndoe@gmail.com", 100, 1);
+        contract.claim_contact(bob_email_contact, U64(100), U64(1), signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_POSITIVE_DEPOSIT_REQUIRED:")]
+    fn storage_deposit_without_deposit() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_ONE_YOCTO_REQUIRED:")]
+    fn start_auth_without_deposit() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_NOT_FOUND:")]
+    fn remove_request_without_request() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.remove_request(CATEGORY_TELEGRAM.to_string());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_NOT_WHITELISTED:")]
+    fn start_auth_without_whitelist() {
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_OF_DIFFERENT_ACCOUNT:")]
+    fn start_auth_by_different_account() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch to a context with different user
+        let context = get_context(bob_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), bob_contact(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_ALREADY_STARTED:")]
+    fn start_auth_twice() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 1, false);
+        testing_env!(context.clone());
+
+        contract.start_auth(alice_request_key(), alice_contact(), None);
+        contract.start_auth(alice_request_key(), bob_contact(), None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_REQUEST_CONTACT_MISSING:")]
+    fn confirm_auth_without_start_auth() {
+        let context = get_context(alice_account(), ntoy(100), false);
+        testing_env!(context.clone());
+
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.storage_deposit(Some(alice_valid_account()));
+
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
+
+// switch back to a context with user
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+
+        contract.confirm_auth(alice_secret_key());
     }
 
     #[test]
-    fn test_storage_deposit_and_withdraw() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_REQUEST_KEY_OR_ACCOUNT_REQUIRED:")]
+    fn cancel_request_without_key_and_account() {
+        let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
-        contract.storage_withdraw();
-
-        assert_eq!(
-            0,
-            contract.storage_paid(alice_valid_account()).0
-        );
+        contract.cancel_request(None, None, false);
     }
 
     #[test]
-    fn whitelist_key() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_CONTACT_VALUE_EMPTY:")]
+    fn start_auth_with_empty_contact() {
+        let context = get_context(alice_account(), 1, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
-
-        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+        contract.start_auth(alice_request_key(), Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: "".to_string(),
+            account_id: None,
+            display_value: None,
+        }, None);
+    }
 
-// switch to a context with master_account
-        let context = get_context(master_account(), 0, false);
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_ACCOUNT_ID_MISSING:")]
+    fn start_auth_with_telegram_contact_without_account_id() {
+        let context = get_context(alice_account(), 1, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
-
-        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
-        assert!(storage_paid_before == storage_paid_after + WHITELIST_STORAGE_COST,
-                "Wrong storage deposit for whitelist {} / {}", storage_paid_before, storage_paid_after);
 
-        let alice_key = contract.get_request_key(alice_account());
-        assert_eq!(alice_key, Some(alice_request_key()), "Key wasn't added");
+        let mut contract = Contract::new(master_valid_account());
 
-        let bob_key = contract.get_request_key(bob_account());
-        assert!(bob_key != Some(bob_request_key()), "Wrong key added");
+        contract.start_auth(alice_request_key(), Contact {
+            category: CATEGORY_TELEGRAM.to_string(),
+            value: "account_123".to_string(),
+            account_id: None,
+            display_value: None,
+        }, None);
+    }
 
-        let alice_has_key = contract.has_request_key(alice_account());
-        assert_eq!(alice_has_key, true, "Key wasn't added");
+    #[test]
+    #[should_panic(expected = "ERR_NOT_CONTACT_OWNER:")]
+    fn remove_contact_of_different_account() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
 
-        let bob_has_key = contract.has_request_key(bob_account());
-        assert_eq!(bob_has_key, false, "Wrong key added");
+        let mut contract = Contract::new(master_valid_account());
 
-        let request: Request = contract.get_request(alice_request_key()).unwrap();
-        assert_eq!(request.account_id, alice_account(), "Key wasn't added");
-        assert!(request.account_id != bob_account(), "Wrong key added");
-        assert!(request.contact == None, "Contact not empty");
+        contract.remove(alice_contact());
     }
 
     #[test]
-    #[should_panic(expected = "No access")]
-    fn whitelist_by_user() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_UNSUPPORTED_KEY_TYPE:")]
+    fn add_secp256k1_verifier_key() {
+        let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
-
-        contract.whitelist_key(alice_valid_account(), bob_request_key());
+        let mut public_key = vec![CurveType::SECP256K1 as u8];
+        public_key.extend_from_slice(&[1u8; 64]);
+        contract.add_verifier_key(Base58PublicKey(public_key), vec![CATEGORY_TELEGRAM.to_string()]);
     }
 
     #[test]
-    #[should_panic(expected = "Request for this account already exist. Please remove it to continue")]
-    fn whitelist_twice() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_VERIFIER_KEY_NOT_FOUND:")]
+    fn remove_unknown_verifier_key() {
+        let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
+        contract.remove_verifier_key(verifier_public_key());
+    }
 
-// switch to a context with master_account
+    #[test]
+    #[should_panic(expected = "ERR_VERIFIER_NOT_FOUND:")]
+    fn remove_unknown_verifier() {
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
 
-        contract.whitelist_key(alice_valid_account(), bob_request_key());
+        let mut contract = Contract::new(master_valid_account());
+
+        contract.remove_verifier(telegram_bot_valid_account());
     }
 
     #[test]
-    #[should_panic(expected = "alice.near requires minimum storage deposit of 10000000000000000000000")]
-    fn whitelist_without_storage() {
-        let context = get_context(alice_account(), ntoy(1) / 1000, false);
+    #[should_panic(expected = "ERR_CATEGORIES_EMPTY:")]
+    fn add_verifier_without_categories() {
+        let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
+        contract.add_verifier(telegram_bot_valid_account(), vec![]);
+    }
 
-// switch to a context with master_account
+    #[test]
+    #[should_panic(expected = "ERR_CATEGORY_NOT_FOUND:")]
+    fn add_verifier_for_unknown_category() {
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
+
+        let mut contract = Contract::new(master_valid_account());
+        contract.add_verifier(master_valid_account(), vec!["Mastodon".to_string()]);
     }
 
     #[test]
-    fn remove_request_after_whitelist() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_CATEGORY_ALREADY_EXISTS:")]
+    fn add_existing_category() {
+        let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
+        contract.add_category(CATEGORY_EMAIL.to_string(), "Email".to_string(), false, vec![], None);
+    }
 
-        contract.storage_deposit(Some(alice_valid_account()));
-        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
-
-// switch to a context with master_account
+    #[test]
+    #[should_panic(expected = "ERR_CATEGORY_ID_INVALID:")]
+    fn add_category_with_invalid_id() {
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
 
-// switch back to a context with user
+        let mut contract = Contract::new(master_valid_account());
+        contract.add_category("Matrix:".to_string(), "Matrix".to_string(), false, vec![], None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn add_category_by_user() {
         let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
 
-        contract.remove_request();
+        let mut contract = Contract::new(master_valid_account());
+        contract.add_category("Matrix".to_string(), "Matrix".to_string(), false, vec![], None);
+    }
 
-        let request: Option<Request> = contract.get_request(alice_request_key());
-        assert!(request == None, "Request was not removed");
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn migrate_legacy_entries_by_user() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
 
-        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
-        assert!(storage_paid_before == storage_paid_after + WHITELIST_FEE,
-                "Wrong storage deposit for remove_request {} / {}", storage_paid_before, storage_paid_after);
+        let mut contract = Contract::new(master_valid_account());
+        contract.migrate_legacy_entries(10);
+    }
 
-// switch to a context with master_account
-        let context = get_context(master_account(), 0, false);
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn set_normalization_rules_by_user() {
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
 
-        let alice_has_key = contract.has_request_key(alice_account());
-        assert_eq!(alice_has_key, true, "Key wasn't added on a second time");
+        let mut contract = Contract::new(master_valid_account());
+        contract.set_normalization_rules(CATEGORY_PHONE.to_string(), vec![NormalizationRule::E164(Some(1))]);
     }
 
     #[test]
-    fn start_auth() {
+    #[should_panic(expected = "ERR_CATEGORY_DEPRECATED:")]
+    fn whitelist_key_for_deprecated_category() {
         let context = get_context(alice_account(), ntoy(100), false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
-
         contract.storage_deposit(Some(alice_valid_account()));
 
 // switch to a context with master_account
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
-
-// switch back to a context with user
-        let context = get_context(alice_account(), 1, false);
-        testing_env!(context.clone());
-
-        contract.start_auth(alice_request_key(), alice_contact());
-
-        let request: Request = contract.get_request(alice_request_key()).unwrap();
-        assert!(request.contact == Some(alice_contact()), "Contact wasn't properly saved");
+        contract.deprecate_category(CATEGORY_TELEGRAM.to_string());
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), CATEGORY_TELEGRAM.to_string(), None);
     }
 
     #[test]
-    fn remove_request_after_start_auth() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_LIMIT_TOO_HIGH:")]
+    fn sweep_expired_requests_with_high_limit() {
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
-        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
+        contract.sweep_expired_requests(0, 101);
+    }
 
-// switch to a context with master_account
-        let context = get_context(master_account(), 0, false);
+    #[test]
+    #[should_panic(expected = "ERR_LIMIT_TOO_HIGH:")]
+    fn get_all_contacts_with_high_limit() {
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
 
-// switch back to a context with user
-        let context = get_context(alice_account(), 1, false);
-        testing_env!(context.clone());
+        let contract = Contract::new(master_valid_account());
 
-        contract.start_auth(alice_request_key(), alice_contact());
+        contract.get_all_contacts(0, 101);
+    }
 
-        contract.remove_request();
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_VALUE_INVALID: Contact value is invalid for the category: EMAIL_MISSING_AT")]
+    fn get_contact_hash_of_invalid_contact() {
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
 
-        let request: Option<Request> = contract.get_request(alice_request_key());
-        assert!(request == None, "Request was not removed");
+        let contract = Contract::new(master_valid_account());
 
-        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
-        assert!(storage_paid_before == storage_paid_after + WHITELIST_FEE,
-                "Wrong storage deposit for remove_request {} / {}", storage_paid_before, storage_paid_after);
+        contract.get_contact_hash(Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: "https://example.com".to_string(),
+            account_id: None,
+            display_value: None,
+        });
     }
 
     #[test]
-    fn confirm_auth() {
+    #[should_panic(expected = "ERR_CONTACT_ALREADY_REGISTERED:")]
+    fn add_email_alias_of_bound_contact() {
         let context = get_context(alice_account(), ntoy(100), false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
         contract.storage_deposit(Some(alice_valid_account()));
-        let storage_paid_before = contract.storage_paid(alice_valid_account()).0;
 
 // switch to a context with master_account
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
+        contract.whitelist_key(alice_valid_account(), alice_email_request_key(), CATEGORY_EMAIL.to_string(), None);
 
 // switch back to a context with user
         let context = get_context(alice_account(), 1, false);
         testing_env!(context.clone());
 
-        contract.start_auth(alice_request_key(), alice_contact());
+        contract.start_auth(alice_email_request_key(), Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: "johndoe@gmail.com".to_string(),
+            account_id: None,
+            display_value: None,
+        }, None);
+        contract.confirm_auth(alice_email_secret_key());
 
-        let secret_key: SecretKey = digest(alice_secret_key());
-        assert!(secret_key == "9f763044a36137644ca87a50545c3eff219345d8490d1c1db597105411315a9a", "Wrong secret key generation");
-        contract.confirm_auth(alice_secret_key());
+// switch to bob
+        let context = get_context(bob_account(), ntoy(100), false);
+        testing_env!(context.clone());
+        contract.storage_deposit(Some(bob_valid_account()));
 
-        let alice_is_owner = contract.is_owner(alice_account(), alice_contact());
-        assert!(alice_is_owner == true, "Contact wasn't created");
+// switch to a context with master_account
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
+        contract.whitelist_key(bob_valid_account(), bob_request_key(), CATEGORY_EMAIL.to_string(), None);
 
-        let bob_is_owner = contract.is_owner(bob_account(), alice_contact());
-        assert!(bob_is_owner == false, "Wrong contact owner");
+// switch back to bob
+        let context = get_context(bob_account(), 1, false);
+        testing_env!(context.clone());
 
-        let storage_paid_after = contract.storage_paid(alice_valid_account()).0;
-        assert!(storage_paid_before > storage_paid_after,
-                "Storage deposit wasn't reduced after adding an item {} / {}", storage_paid_before, storage_paid_after);
+        contract.start_auth(bob_request_key(), Contact {
+            category: CATEGORY_EMAIL.to_string(),
+            value: "John.Doe+near@gmail.com".to_string(),
+            account_id: None,
+            display_value: None,
+        }, None);
     }
 
     #[test]
-    #[should_panic(expected = "Request not found")]
-    fn confirm_auth_with_wrong_key() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_EMAIL_DOMAIN_RULE_NOT_FOUND:")]
+    fn remove_unknown_email_domain_rule() {
+        let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
+        contract.remove_email_domain_rule("proton.me".to_string());
+    }
 
-// switch to a context with master_account
-        let context = get_context(master_account(), 0, false);
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_ALREADY_REGISTERED:")]
+    fn claim_bound_private_contact() {
+        let mut contract = get_contract_with_verifier_key();
+
+        let context = get_context(bob_account(), ntoy(100), false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
+        contract.storage_deposit(None);
 
-// switch back to a context with user
-        let context = get_context(alice_account(), 1, false);
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        let signature = sign_claim(alice_account(), "Email:alice@example.com".to_string(), "alice@example.com", 100, 1);
+        contract.claim_contact(alice_email_contact(), U64(100), U64(1), signature, Some(true));
+
+        let context = get_context(bob_account(), 0, false);
+        testing_env!(context.clone());
+        let signature = sign_claim(bob_account(), "Email:alice@example.com".to_string(), "alice@example.com", 100, 2);
+        contract.claim_contact(alice_email_contact(), U64(100), U64(2), signature, None);
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_VALUE_INVALID: Contact value is invalid for the category: PHONE_MISSING_COUNTRY_CODE")]
+    fn get_contact_hash_of_national_phone() {
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
 
-        contract.start_auth(alice_request_key(), alice_contact());
+        let contract = Contract::new(master_valid_account());
 
-        contract.confirm_auth(bob_secret_key());
+        contract.get_contact_hash(Contact {
+            category: CATEGORY_PHONE.to_string(),
+            value: "(415) 555-2671".to_string(),
+            account_id: None,
+            display_value: None,
+        });
     }
 
     #[test]
-    #[should_panic(expected = "No access to confirm this request")]
-    fn confirm_auth_with_wrong_user() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_NOT_CONTACT_OWNER:")]
+    fn hide_unknown_contact() {
+        let mut contract = get_contract_with_verifier_key();
+
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
+        contract.set_discovery_settings(false, vec![], vec![alice_email_contact()]);
+    }
 
-        let mut contract = Contract::new(master_valid_account());
+    #[test]
+    #[should_panic(expected = "ERR_NOT_CONTACT_OWNER:")]
+    fn set_primary_contact_of_unknown_contact() {
+        let mut contract = get_contract_with_verifier_key();
 
-        contract.storage_deposit(Some(alice_valid_account()));
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        contract.set_primary_contact(alice_email_contact());
+    }
 
-// switch to a context with master_account
-        let context = get_context(master_account(), 0, false);
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_STALE:")]
+    fn send_to_stale_contact() {
+        let mut contract = get_contract_with_expiring_contact();
+
+// send from bob
+        let mut context = get_context(bob_account(), ntoy(75), false);
+        context.block_timestamp = 101;
+        testing_env!(context.clone());
+        contract.send(alice_contact());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn reverify_contact_by_user() {
+        let mut contract = get_contract_with_expiring_contact();
+
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
+        contract.reverify_contact(alice_valid_account(), alice_contact());
+    }
+
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_ALREADY_REGISTERED:")]
+    fn confirm_auth_of_contact_claimed_after_start() {
+        let mut contract = get_contract_with_verifier_key();
+        contract.whitelist_key(alice_valid_account(), alice_email_request_key(), CATEGORY_EMAIL.to_string(), None);
 
 // switch back to a context with user
         let context = get_context(alice_account(), 1, false);
         testing_env!(context.clone());
+        contract.start_auth(alice_email_request_key(), alice_email_contact(), None);
 
-        contract.start_auth(alice_request_key(), alice_contact());
-
-// switch back to a context with user
-        let context = get_context(bob_account(), 1, false);
+// switch to bob, current account is alice.near in claim contexts
+        let mut context = get_context(bob_account(), ntoy(100), false);
+        context.current_account_id = alice_account();
         testing_env!(context.clone());
+        contract.storage_deposit(Some(bob_valid_account()));
+        let signature = sign_claim(bob_account(), "Email:alice@example.com".to_string(), "alice@example.com", 100, 1);
+        contract.claim_contact(alice_email_contact(), U64(100), U64(1), signature, None);
 
-        contract.confirm_auth(alice_secret_key());
+// switch back to alice
+        let context = get_context(alice_account(), 0, false);
+        testing_env!(context.clone());
+        contract.confirm_auth(alice_email_secret_key());
     }
 
     #[test]
-    #[should_panic(expected = "Contact already registered")]
-    fn add_same_contact_twice() {
+    #[should_panic(expected = "ERR_CONTACT_CONFUSABLE:")]
+    fn add_confusable_contact() {
         let context = get_context(alice_account(), ntoy(100), false);
         testing_env!(context.clone());
 
@@ -939,17 +4671,24 @@ mod tests {
 // switch to a context with master_account
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
+        contract.add_category("Mastodon".to_string(), "Mastodon".to_string(), false, vec![NormalizationRule::Nfkc, NormalizationRule::Lowercase], None);
+        contract.add_verifier(master_valid_account(), vec!["Mastodon".to_string()]);
+        contract.whitelist_key(alice_valid_account(), alice_request_key(), "Mastodon".to_string(), None);
 
 // switch back to a context with user
         let context = get_context(alice_account(), 1, false);
         testing_env!(context.clone());
 
-        contract.start_auth(alice_request_key(), alice_contact());
+        let mastodon_contact = |value: &str| Contact {
+            category: "Mastodon".to_string(),
+            value: value.to_string(),
+            account_id: None,
+            display_value: None,
+        };
+        contract.start_auth(alice_request_key(), mastodon_contact("alice@mastodon.social"), None);
         contract.confirm_auth(alice_secret_key());
 
 // switch to bob
-
         let context = get_context(bob_account(), ntoy(100), false);
         testing_env!(context.clone());
         contract.storage_deposit(Some(bob_valid_account()));
@@ -957,74 +4696,83 @@ mod tests {
 // switch to a context with master_account
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(bob_valid_account(), bob_request_key());
+        contract.whitelist_key(bob_valid_account(), bob_request_key(), "Mastodon".to_string(), None);
 
-// switch back to a context with user
-        let context = get_context(alice_account(), 1, false);
+// switch back to bob
+        let context = get_context(bob_account(), 1, false);
         testing_env!(context.clone());
 
-        contract.start_auth(alice_request_key(), alice_contact());
-        contract.confirm_auth(bob_secret_key());
+        contract.start_auth(bob_request_key(), mastodon_contact("\u{430}lice@mastodon.social"), None);
     }
 
     #[test]
-    fn remove_contact() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_CONTACT_ACCOUNT_ID_MISSING:")]
+    fn get_contact_hash_of_github_contact_without_id() {
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
 
-        let mut contract = Contract::new(master_valid_account());
+        let contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
+        contract.get_contact_hash(Contact {
+            category: CATEGORY_GITHUB.to_string(),
+            value: "octocat".to_string(),
+            account_id: None,
+            display_value: None,
+        });
+    }
 
-// switch to a context with master_account
+    #[test]
+    #[should_panic(expected = "ERR_CATEGORY_WITHOUT_STABLE_ID:")]
+    fn set_contact_stable_id_of_email() {
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
 
-// switch back to a context with user
-        let context = get_context(alice_account(), 1, false);
-        testing_env!(context.clone());
+        let mut contract = Contract::new(master_valid_account());
 
-        contract.start_auth(alice_request_key(), alice_contact());
-        contract.confirm_auth(alice_secret_key());
+        contract.set_contact_stable_id(alice_valid_account(), CATEGORY_EMAIL.to_string(), "alice@example.com".to_string(), U64(1));
+    }
 
-        assert!(contract.is_owner(alice_account(), alice_contact()) == true, "Contact wasn't created");
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_NOT_FOUND:")]
+    fn set_contact_stable_id_of_unknown_contact() {
+        let context = get_context(master_account(), 0, false);
+        testing_env!(context.clone());
 
-        contract.remove(alice_contact());
+        let mut contract = Contract::new(master_valid_account());
 
-        assert!(contract.is_owner(alice_account(), alice_contact()) == false, "Contact wasn't removed");
+        contract.set_contact_stable_id(alice_valid_account(), CATEGORY_GITHUB.to_string(), "octocat".to_string(), U64(583231));
     }
 
     #[test]
-    #[should_panic(expected = "Contact not found")]
-    fn send_to_contact() {
-        let context = get_context(alice_account(), ntoy(100), false);
+    #[should_panic(expected = "ERR_NO_ACCESS:")]
+    fn update_contact_display_by_user() {
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
 
         let mut contract = Contract::new(master_valid_account());
 
-        contract.storage_deposit(Some(alice_valid_account()));
+        contract.update_contact_display(alice_valid_account(), CATEGORY_TELEGRAM.to_string(), U64(1), "alice_renamed".to_string());
+    }
 
-// switch to a context with master_account
+    #[test]
+    #[should_panic(expected = "ERR_CONTACT_NOT_FOUND:")]
+    fn update_contact_display_of_unknown_contact() {
         let context = get_context(master_account(), 0, false);
         testing_env!(context.clone());
-        contract.whitelist_key(alice_valid_account(), alice_request_key());
 
-// switch back to a context with user
-        let context = get_context(alice_account(), 1, false);
-        testing_env!(context.clone());
-
-        contract.start_auth(alice_request_key(), alice_contact());
-        contract.confirm_auth(alice_secret_key());
+        let mut contract = Contract::new(master_valid_account());
 
-// send from bob
+        contract.update_contact_display(alice_valid_account(), CATEGORY_TELEGRAM.to_string(), U64(1), "alice_renamed".to_string());
+    }
 
-        let context = get_context(bob_account(), ntoy(75), false);
+    #[test]
+    #[should_panic(expected = "ERR_DEPRECATED:")]
+    fn get_owners() {
+        let context = get_context(alice_account(), 0, false);
         testing_env!(context.clone());
-        contract.send(alice_contact());
 
-        let context = get_context(bob_account(), ntoy(75), false);
-        testing_env!(context.clone());
-        contract.send(bob_contact());
+        let contract = Contract::new(master_valid_account());
+
+        contract.get_owners(alice_contact());
     }
 }
@@ -0,0 +1,218 @@
+use near_sdk::borsh::{self, BorshDeserialize, BorshSerialize};
+use near_sdk::serde::{Deserialize, Serialize};
+
+const EMAIL_MAX_LENGTH: usize = 254;
+const EMAIL_LOCAL_PART_MAX_LENGTH: usize = 64;
+const EMAIL_DOMAIN_LABEL_MAX_LENGTH: usize = 63;
+const GITHUB_MAX_LENGTH: usize = 39;
+const TWITTER_MAX_LENGTH: usize = 15;
+const TELEGRAM_MIN_LENGTH: usize = 5;
+const TELEGRAM_MAX_LENGTH: usize = 32;
+const FORUM_MIN_LENGTH: usize = 3;
+const FORUM_MAX_LENGTH: usize = 20;
+const PHONE_MIN_DIGITS: usize = 7;
+const PHONE_MAX_DIGITS: usize = 15;
+const DISCORD_MIN_LENGTH: usize = 2;
+const DISCORD_MAX_LENGTH: usize = 32;
+const DISCORD_RESERVED_NAMES: &[&str] = &["everyone", "here"];
+
+/// Country calling codes of ITU-T E.164 with min and max length of the national significant number.
+/// Codes are prefix-free, numbers of the codes missing here are checked by E.164 length only.
+const PHONE_COUNTRY_CODES: &[(&str, usize, usize)] = &[
+    ("1", 10, 10), ("7", 10, 10),
+    ("20", 9, 10), ("27", 9, 9), ("30", 10, 10), ("31", 9, 9), ("32", 8, 9), ("33", 9, 9), ("34", 9, 9),
+    ("36", 8, 9), ("39", 6, 11), ("40", 9, 9), ("41", 9, 9), ("43", 4, 13), ("44", 9, 10), ("45", 8, 8),
+    ("46", 7, 13), ("47", 8, 8), ("48", 9, 9), ("49", 6, 14), ("51", 8, 9), ("52", 10, 10), ("53", 6, 8),
+    ("54", 10, 11), ("55", 10, 11), ("56", 9, 9), ("57", 10, 10), ("58", 10, 10), ("60", 8, 10), ("61", 9, 9),
+    ("62", 8, 12), ("63", 8, 10), ("64", 8, 10), ("65", 8, 8), ("66", 8, 9), ("81", 9, 10), ("82", 8, 11),
+    ("84", 9, 10), ("86", 10, 11), ("90", 10, 10), ("91", 10, 10), ("92", 9, 10), ("93", 9, 9), ("94", 9, 9),
+    ("95", 7, 10), ("98", 10, 10),
+    ("212", 9, 9), ("213", 8, 9), ("216", 8, 8), ("234", 8, 10), ("254", 9, 9), ("255", 9, 9), ("256", 9, 9),
+    ("351", 9, 9), ("352", 4, 11), ("353", 7, 9), ("354", 7, 7), ("358", 5, 12), ("359", 8, 9), ("370", 8, 8),
+    ("371", 8, 8), ("372", 7, 8), ("380", 9, 9), ("381", 6, 12), ("385", 8, 9), ("420", 9, 9), ("421", 9, 9),
+    ("852", 8, 8), ("853", 8, 8), ("880", 10, 10), ("886", 8, 9), ("966", 9, 9), ("971", 8, 9), ("972", 8, 9),
+    ("974", 8, 8), ("995", 9, 9), ("998", 9, 9),
+];
+
+/// Validator of the normalized contact value, assigned to the category in the registry.
+#[derive(Clone, Copy, BorshDeserialize, BorshSerialize, Serialize, Deserialize, Eq, PartialEq, Debug)]
+#[serde(crate = "near_sdk::serde")]
+pub enum ContactValidator {
+    Email,
+    Github,
+    Twitter,
+    Telegram,
+    Forum,
+    Phone,
+    Discord,
+}
+
+/// Reason of the rejected contact value, added to `ERR_CONTACT_VALUE_INVALID` panic message.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum InvalidContactReason {
+    TooShort,
+    TooLong,
+    InvalidCharacter,
+    InvalidFirstCharacter,
+    InvalidLastCharacter,
+    ConsecutiveSpecialCharacters,
+    EmailMissingAt,
+    EmailLocalPartEmpty,
+    EmailLocalPartTooLong,
+    EmailLocalPartInvalidDot,
+    EmailDomainNotQualified,
+    EmailDomainLabelInvalid,
+    PhoneMissingCountryCode,
+    PhoneCountryCodeUnknown,
+    DiscordDiscriminatorInvalid,
+    DiscordNameReserved,
+}
+
+impl InvalidContactReason {
+    pub fn code(&self) -> &'static str {
+        match self {
+            InvalidContactReason::TooShort => "TOO_SHORT",
+            InvalidContactReason::TooLong => "TOO_LONG",
+            InvalidContactReason::InvalidCharacter => "INVALID_CHARACTER",
+            InvalidContactReason::InvalidFirstCharacter => "INVALID_FIRST_CHARACTER",
+            InvalidContactReason::InvalidLastCharacter => "INVALID_LAST_CHARACTER",
+            InvalidContactReason::ConsecutiveSpecialCharacters => "CONSECUTIVE_SPECIAL_CHARACTERS",
+            InvalidContactReason::EmailMissingAt => "EMAIL_MISSING_AT",
+            InvalidContactReason::EmailLocalPartEmpty => "EMAIL_LOCAL_PART_EMPTY",
+            InvalidContactReason::EmailLocalPartTooLong => "EMAIL_LOCAL_PART_TOO_LONG",
+            InvalidContactReason::EmailLocalPartInvalidDot => "EMAIL_LOCAL_PART_INVALID_DOT",
+            InvalidContactReason::EmailDomainNotQualified => "EMAIL_DOMAIN_NOT_QUALIFIED",
+            InvalidContactReason::EmailDomainLabelInvalid => "EMAIL_DOMAIN_LABEL_INVALID",
+            InvalidContactReason::PhoneMissingCountryCode => "PHONE_MISSING_COUNTRY_CODE",
+            InvalidContactReason::PhoneCountryCodeUnknown => "PHONE_COUNTRY_CODE_UNKNOWN",
+            InvalidContactReason::DiscordDiscriminatorInvalid => "DISCORD_DISCRIMINATOR_INVALID",
+            InvalidContactReason::DiscordNameReserved => "DISCORD_NAME_RESERVED",
+        }
+    }
+}
+
+impl ContactValidator {
+    pub fn validate(&self, value: &str) -> Result<(), InvalidContactReason> {
+        match self {
+            ContactValidator::Email => validate_email(value),
+            ContactValidator::Github => validate_github(value),
+            ContactValidator::Twitter => validate_twitter(value),
+            ContactValidator::Telegram => validate_telegram(value),
+            ContactValidator::Forum => validate_forum(value),
+            ContactValidator::Phone => validate_phone(value),
+            ContactValidator::Discord => validate_discord(value),
+        }
+    }
+}
+
+fn require(condition: bool, reason: InvalidContactReason) -> Result<(), InvalidContactReason> {
+    if condition { Ok(()) } else { Err(reason) }
+}
+
+fn validate_length(value: &str, min_length: usize, max_length: usize) -> Result<(), InvalidContactReason> {
+    let length = value.chars().count();
+    require(length >= min_length, InvalidContactReason::TooShort)?;
+    require(length <= max_length, InvalidContactReason::TooLong)
+}
+
+/// Dot-atom addresses of RFC 5321, quoted local parts and address literals are not accepted.
+fn validate_email(value: &str) -> Result<(), InvalidContactReason> {
+    require(value.len() <= EMAIL_MAX_LENGTH, InvalidContactReason::TooLong)?;
+
+    let (local_part, domain) = value.rsplit_once('@').ok_or(InvalidContactReason::EmailMissingAt)?;
+
+    require(!local_part.is_empty(), InvalidContactReason::EmailLocalPartEmpty)?;
+    require(local_part.len() <= EMAIL_LOCAL_PART_MAX_LENGTH, InvalidContactReason::EmailLocalPartTooLong)?;
+    require(
+        local_part.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || "!#$%&'*+-/=?^_`{|}~".contains(c)),
+        InvalidContactReason::InvalidCharacter,
+    )?;
+    require(local_part.split('.').all(|atom| !atom.is_empty()), InvalidContactReason::EmailLocalPartInvalidDot)?;
+
+    require(domain.contains('.'), InvalidContactReason::EmailDomainNotQualified)?;
+    require(
+        domain.split('.').all(|label| {
+            !label.is_empty()
+                && label.len() <= EMAIL_DOMAIN_LABEL_MAX_LENGTH
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        }),
+        InvalidContactReason::EmailDomainLabelInvalid,
+    )
+}
+
+/// Alphanumerics and single hyphens, can't start or end with a hyphen.
+fn validate_github(value: &str) -> Result<(), InvalidContactReason> {
+    validate_length(value, 1, GITHUB_MAX_LENGTH)?;
+    require(value.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'), InvalidContactReason::InvalidCharacter)?;
+    require(!value.starts_with('-'), InvalidContactReason::InvalidFirstCharacter)?;
+    require(!value.ends_with('-'), InvalidContactReason::InvalidLastCharacter)?;
+    require(!value.contains("--"), InvalidContactReason::ConsecutiveSpecialCharacters)
+}
+
+fn validate_twitter(value: &str) -> Result<(), InvalidContactReason> {
+    validate_length(value, 1, TWITTER_MAX_LENGTH)?;
+    require(value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'), InvalidContactReason::InvalidCharacter)
+}
+
+/// Starts with a letter and can't end with an underscore.
+fn validate_telegram(value: &str) -> Result<(), InvalidContactReason> {
+    validate_length(value, TELEGRAM_MIN_LENGTH, TELEGRAM_MAX_LENGTH)?;
+    require(value.chars().all(|c| c.is_ascii_alphanumeric() || c == '_'), InvalidContactReason::InvalidCharacter)?;
+    require(value.starts_with(|c: char| c.is_ascii_alphabetic()), InvalidContactReason::InvalidFirstCharacter)?;
+    require(!value.ends_with('_'), InvalidContactReason::InvalidLastCharacter)
+}
+
+/// Discourse usernames: alphanumerics, `_`, `-` and `.`, special characters can't be repeated or end the name.
+fn validate_forum(value: &str) -> Result<(), InvalidContactReason> {
+    let is_special = |c: char| c == '_' || c == '-' || c == '.';
+
+    validate_length(value, FORUM_MIN_LENGTH, FORUM_MAX_LENGTH)?;
+    require(value.chars().all(|c| c.is_ascii_alphanumeric() || is_special(c)), InvalidContactReason::InvalidCharacter)?;
+    require(value.starts_with(|c: char| c.is_ascii_alphanumeric() || c == '_'), InvalidContactReason::InvalidFirstCharacter)?;
+    require(value.ends_with(|c: char| c.is_ascii_alphanumeric()), InvalidContactReason::InvalidLastCharacter)?;
+    require(
+        !value.chars().zip(value.chars().skip(1)).any(|(c1, c2)| is_special(c1) && is_special(c2)),
+        InvalidContactReason::ConsecutiveSpecialCharacters,
+    )
+}
+
+/// E.164 number: `+`, country calling code and national significant number of the length allowed for the code.
+fn validate_phone(value: &str) -> Result<(), InvalidContactReason> {
+    let digits = value.strip_prefix('+').ok_or(InvalidContactReason::PhoneMissingCountryCode)?;
+
+    require(digits.chars().all(|c| c.is_ascii_digit()), InvalidContactReason::InvalidCharacter)?;
+    require(digits.len() <= PHONE_MAX_DIGITS, InvalidContactReason::TooLong)?;
+
+    require(!digits.starts_with('0'), InvalidContactReason::PhoneCountryCodeUnknown)?;
+
+    match PHONE_COUNTRY_CODES.iter().find(|(country_code, _, _)| digits.starts_with(country_code)) {
+        Some((country_code, min_length, max_length)) => validate_length(&digits[country_code.len()..], *min_length, *max_length),
+        None => validate_length(digits, PHONE_MIN_DIGITS, PHONE_MAX_DIGITS)
+    }
+}
+
+/// Legacy `name#1234` tag, or new-style username of lowercase alphanumerics, `_` and single dots.
+fn validate_discord(value: &str) -> Result<(), InvalidContactReason> {
+    match value.rsplit_once('#') {
+        Some((name, discriminator)) => {
+            require(
+                discriminator.len() == 4 && discriminator.chars().all(|c| c.is_ascii_digit()) && discriminator != "0000",
+                InvalidContactReason::DiscordDiscriminatorInvalid,
+            )?;
+            validate_length(name, DISCORD_MIN_LENGTH, DISCORD_MAX_LENGTH)?;
+            require(!name.contains(|c| "@#:".contains(c)) && !name.contains("```"), InvalidContactReason::InvalidCharacter)?;
+            require(!DISCORD_RESERVED_NAMES.contains(&name), InvalidContactReason::DiscordNameReserved)
+        }
+        None => {
+            validate_length(value, DISCORD_MIN_LENGTH, DISCORD_MAX_LENGTH)?;
+            require(
+                value.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_' || c == '.'),
+                InvalidContactReason::InvalidCharacter,
+            )?;
+            require(!value.contains(".."), InvalidContactReason::ConsecutiveSpecialCharacters)?;
+            require(!DISCORD_RESERVED_NAMES.contains(&value), InvalidContactReason::DiscordNameReserved)
+        }
+    }
+}
@@ -84,7 +84,7 @@ export default function App() {
                         onClick={async event => {
                             event.preventDefault()
                             try {
-                                await window.contract.remove_request({}, FULL_GAS);
+                                await window.contract.remove_request({category: contactType}, FULL_GAS);
                                 setWhiteListedKeyRemove(false);
                             } catch (e) {
                                 alert(
@@ -145,12 +145,13 @@ export default function App() {
                                 setWarning("");
 
                                 data.category = contactType.toLowerCase()
+                                data.request_category = contactType
                                 window.localStorage.setItem('request', data ? JSON.stringify(data) : "[]");
 
                                 try {
                                     await window.contract.start_auth({
                                         request_key: data.request_key,
-                                        contact: {category: contactType, value: data.contact},
+                                        contact: {category: contactType, value: data.contact, account_id: String(data.value)},
                                     }, FULL_GAS, 1)
                                 } catch (e) {
                                     ContractCallAlert();
@@ -337,11 +338,13 @@ export default function App() {
                         const query = JSON.parse(JSON.stringify(queryString.parse(location.search)));
                         if (query && query.hasOwnProperty("key") && query.hasOwnProperty("contact") && query.hasOwnProperty("type")) {
                             const request_key = await window.contract.get_request_key({
-                                account_id: window.accountId
+                                account_id: window.accountId,
+                                category: query.type
                             });
 
                             const has_request_key = await window.contract.has_request_key({
-                                account_id: window.accountId
+                                account_id: window.accountId,
+                                category: query.type
                             });
 
                             if (has_request_key && request_key) {
@@ -500,8 +503,10 @@ export default function App() {
 
     const GetRequest = async () => {
         try {
+            const storedRequest = JSON.parse(window.localStorage.getItem('request') || "[]");
             const request_key = await window.contract.get_request_key({
-                account_id: window.accountId
+                account_id: window.accountId,
+                category: storedRequest.request_category || contactType
             });
 
             if (request_key) {
@@ -674,7 +679,8 @@ export default function App() {
 
 
                     const has_request_key = await window.contract.has_request_key({
-                        account_id: window.accountId
+                        account_id: window.accountId,
+                        category: contactType
                     });
 
                     if (has_request_key) {
@@ -713,6 +719,7 @@ export default function App() {
                                         console.log(contact);
                                         data.contact = contact.value;
                                         data.contact_type = contactType.toLowerCase()
+                                        data.request_category = contactType
                                         window.localStorage.setItem('request', data ? JSON.stringify(data) : "[]");
 
                                         let contactMetadata =
@@ -720,7 +727,7 @@ export default function App() {
                                                 ? {
                                                     category: contactType,
                                                     value: contact.value,
-                                                    account_id: Number(data.value)
+                                                    account_id: String(data.value)
                                                 }
                                                 : {
                                                     category: contactType,